@@ -1,15 +1,35 @@
 use crate::utils::{
-    RebalancingCycle, RebalancingSimulator, calculate_aggregated_apy,
-    calculate_stake_utilization_rate,
+    AllocationMode, CycleTurnover, DiversityConstraints, EpochDecentralization, InflationCurve,
+    InflationSchedule, PriorityFeeRewardModel, RebalancingCycle, RebalancingSimulator,
+    RewardEligibility, RugExposure, Scenario, SimulationOptions, SkipRateScoring,
+    StakeFlowAttribution, StakePoolFeeModel, UnattributedStakeFlowPolicy, ValidatorOverride,
+    calculate_aggregated_apy, calculate_net_aggregated_apy, calculate_stake_utilization_rate,
+    client_type_name, read_validator_overrides, simulated_exchange_rate,
+};
+use crate::{
+    error::CliError,
+    modify_config_parameter_from_args,
+    steward_utils::{fetch_config, fetch_stake_pool},
 };
-use crate::{error::CliError, modify_config_parameter_from_args, steward_utils::fetch_config};
 use clap::Parser;
 use jito_steward::Config;
 use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
 use sqlx::{Pool, Postgres};
+use stakenet_simulator_db::{
+    stake_pool_snapshots::{StakePoolSnapshot, annualized_exchange_rate_change},
+    steward_config_history::StewardConfigHistory,
+};
+use std::path::PathBuf;
 use tracing::info;
 
 pub const DAYS_PER_YEAR: f64 = 365.0;
+// TODO: Should we pull the current epoch from RPC or make it be a CLI argument?
+pub const CURRENT_EPOCH: u16 = 840;
+// TODO: Determine how this should be passed. The number of epochs to look back
+pub const LOOK_BACK_PERIOD: u16 = 50;
+// TODO: Determine if this should be an argument
+pub const NUMBER_OF_VALIDATOR_DELEGATIONS: usize = 200;
 
 #[derive(Clone, Debug, Parser)]
 pub struct BacktestArgs {
@@ -62,7 +82,77 @@ pub struct BacktestArgs {
     #[arg(long, env)]
     target_epoch: Option<u64>,
     #[arg(long, env, default_value = "10")]
-    steward_cycle_rate: u16,
+    pub steward_cycle_rate: u16,
+    #[arg(long, env, default_value_t = false)]
+    simulate_pool_tvl: bool,
+    #[arg(long, env, default_value_t = false)]
+    fees_from_stake_pool: bool,
+    #[arg(long, env)]
+    epoch_fee_bps: Option<u16>,
+    #[arg(long, env)]
+    stake_deposit_fee_bps: Option<u16>,
+    #[arg(long, env)]
+    stake_withdrawal_fee_bps: Option<u16>,
+    #[arg(long, env)]
+    sol_deposit_fee_bps: Option<u16>,
+    #[arg(long, env)]
+    sol_withdrawal_fee_bps: Option<u16>,
+    #[arg(long, env)]
+    stake_referral_fee_pct: Option<u8>,
+    #[arg(long, env)]
+    sol_referral_fee_pct: Option<u8>,
+    #[arg(long, env, value_enum, default_value_t = StakeFlowAttribution::Random)]
+    stake_flow_attribution: StakeFlowAttribution,
+    #[arg(long, env, value_enum, default_value_t = UnattributedStakeFlowPolicy::ProRata)]
+    unattributed_stake_flow: UnattributedStakeFlowPolicy,
+    #[arg(long, env, default_value_t = false)]
+    point_in_time: bool,
+    #[arg(long, env, default_value_t = false)]
+    report_look_ahead_bias: bool,
+    #[arg(long, env)]
+    blacklist_file: Option<PathBuf>,
+    #[arg(long, env)]
+    force_include_file: Option<PathBuf>,
+    #[arg(long, env, default_value_t = false)]
+    pub replay_historical_config: bool,
+    #[arg(long, env, value_enum, default_value_t = RewardEligibility::ActiveOnly)]
+    reward_eligibility: RewardEligibility,
+    #[arg(long, env, default_value_t = false)]
+    report_reward_eligibility_bias: bool,
+    #[arg(long, env, default_value_t = false)]
+    simulate_stake_deposit_unstake: bool,
+    #[arg(long, env, default_value_t = 5_000)]
+    crank_transaction_fee_lamports: u64,
+    #[arg(long, env)]
+    max_client_type_share_bps: Option<u16>,
+    #[arg(long, env)]
+    max_validators_per_subnet: Option<usize>,
+    #[arg(long, env, default_value_t = false)]
+    exclude_superminority: bool,
+    #[arg(long, env)]
+    max_validator_stake_bps: Option<u16>,
+    #[arg(long, env, value_enum, default_value_t = AllocationMode::EqualWeight)]
+    allocation_mode: AllocationMode,
+    #[arg(long, env)]
+    max_turnover_bps: Option<u16>,
+    #[arg(long, env)]
+    scenario: Option<PathBuf>,
+    #[arg(long, env, value_enum, default_value_t = InflationCurve::Historical)]
+    inflation_curve: InflationCurve,
+    #[arg(long, env, default_value_t = 0.08)]
+    inflation_initial_rate: f64,
+    #[arg(long, env, default_value_t = 0.15)]
+    inflation_disinflation_rate: f64,
+    #[arg(long, env, default_value_t = 0.015)]
+    inflation_terminal_rate: f64,
+    #[arg(long, env)]
+    inflation_constant_rate: Option<f64>,
+    #[arg(long, env, value_enum, default_value_t = PriorityFeeRewardModel::Recorded)]
+    priority_fee_reward_model: PriorityFeeRewardModel,
+    #[arg(long, env)]
+    max_skip_rate: Option<f64>,
+    #[arg(long, env, default_value_t = 0.0)]
+    skip_rate_score_weight: f64,
 }
 
 impl BacktestArgs {
@@ -95,6 +185,121 @@ impl BacktestArgs {
         modify_config_parameter_from_args!(self, config, minimum_voting_epochs);
         modify_config_parameter_from_args!(self, config, priority_fee_scoring_start_epoch);
     }
+
+    pub fn update_fee_model(&self, fee_model: &mut StakePoolFeeModel) {
+        modify_config_parameter_from_args!(self, fee_model => epoch_fee_bps);
+        modify_config_parameter_from_args!(self, fee_model => stake_deposit_fee_bps);
+        modify_config_parameter_from_args!(self, fee_model => stake_withdrawal_fee_bps);
+        modify_config_parameter_from_args!(self, fee_model => sol_deposit_fee_bps);
+        modify_config_parameter_from_args!(self, fee_model => sol_withdrawal_fee_bps);
+        modify_config_parameter_from_args!(self, fee_model => stake_referral_fee_pct);
+        modify_config_parameter_from_args!(self, fee_model => sol_referral_fee_pct);
+    }
+
+    pub fn simulation_options(
+        &self,
+        fee_model: StakePoolFeeModel,
+        parameter_schedule: Vec<StewardConfigHistory>,
+    ) -> Result<SimulationOptions, CliError> {
+        if self.inflation_curve == InflationCurve::Constant
+            && self.inflation_constant_rate.is_none()
+        {
+            return Err(CliError::MissingInflationConstantRate);
+        }
+
+        Ok(SimulationOptions {
+            simulate_pool_tvl: self.simulate_pool_tvl,
+            fee_model,
+            stake_flow_attribution: self.stake_flow_attribution,
+            unattributed_stake_flow: self.unattributed_stake_flow,
+            point_in_time: self.point_in_time,
+            blacklist: Self::read_overrides(&self.blacklist_file)?,
+            force_include: Self::read_overrides(&self.force_include_file)?,
+            parameter_schedule,
+            reward_eligibility: self.reward_eligibility,
+            simulate_stake_deposit_unstake: self.simulate_stake_deposit_unstake,
+            diversity_constraints: DiversityConstraints {
+                max_client_type_share_bps: self.max_client_type_share_bps,
+                max_validators_per_subnet: self.max_validators_per_subnet,
+                exclude_superminority: self.exclude_superminority,
+                max_validator_stake_bps: self.max_validator_stake_bps,
+            },
+            allocation_mode: self.allocation_mode,
+            max_turnover_bps: self.max_turnover_bps,
+            scenario: match &self.scenario {
+                Some(path) => Scenario::read(path)?,
+                None => Scenario::default(),
+            },
+            inflation_schedule: InflationSchedule {
+                curve: self.inflation_curve,
+                initial_rate: self.inflation_initial_rate,
+                disinflation_rate: self.inflation_disinflation_rate,
+                terminal_rate: self.inflation_terminal_rate,
+                constant_rate: self.inflation_constant_rate,
+            },
+            priority_fee_reward_model: self.priority_fee_reward_model,
+            skip_rate_scoring: SkipRateScoring {
+                max_skip_rate: self.max_skip_rate,
+                score_weight: self.skip_rate_score_weight,
+            },
+        })
+    }
+
+    /// Loads the steward parameters recorded on-chain up to `end_epoch` and applies the CLI
+    /// overrides on top of each of them, so only the parameters being tested deviate from history
+    pub async fn historical_parameter_schedule(
+        &self,
+        db_connection: &Pool<Postgres>,
+        steward_config: &Config,
+        end_epoch: u16,
+    ) -> Result<Vec<StewardConfigHistory>, CliError> {
+        let records = StewardConfigHistory::fetch_up_to_epoch(db_connection, end_epoch).await?;
+        if records.is_empty() {
+            return Err(CliError::ParameterScheduleNotFound(end_epoch));
+        }
+        info!("Replaying {} recorded steward configs", records.len());
+
+        Ok(records
+            .into_iter()
+            .map(|record| {
+                let mut config = steward_config.clone();
+                record.apply_to(&mut config.parameters);
+                self.update_steward_config(&mut config);
+                StewardConfigHistory::from_parameters(record.epoch, &config.parameters)
+            })
+            .collect())
+    }
+
+    /// Loads the existing steward config and overwrites parameters based on CLI args
+    pub async fn load_steward_config(&self, rpc_client: &RpcClient) -> Result<Config, CliError> {
+        let mut steward_config = fetch_config(rpc_client).await?;
+        self.update_steward_config(&mut steward_config);
+        Ok(steward_config)
+    }
+
+    /// Loads the pool's fees if requested and overwrites them based on CLI args
+    pub async fn load_fee_model(
+        &self,
+        rpc_client: &RpcClient,
+        steward_config: &Config,
+    ) -> Result<StakePoolFeeModel, CliError> {
+        let mut fee_model = if self.fees_from_stake_pool {
+            let stake_pool = fetch_stake_pool(rpc_client, &steward_config.stake_pool).await?;
+            StakePoolFeeModel::from_stake_pool(&stake_pool)
+        } else {
+            StakePoolFeeModel::default()
+        };
+        self.update_fee_model(&mut fee_model);
+        info!("Stake pool fee model: {:?}", fee_model);
+        Ok(fee_model)
+    }
+
+    fn read_overrides(path: &Option<PathBuf>) -> Result<Vec<ValidatorOverride>, CliError> {
+        match path {
+            Some(path) => read_validator_overrides(path),
+            None => Ok(Vec::new()),
+        }
+    }
 }
 
 pub async fn handle_backtest(
@@ -102,18 +307,21 @@ pub async fn handle_backtest(
     db_connection: &Pool<Postgres>,
     rpc_client: &RpcClient,
 ) -> Result<(), CliError> {
-    // TODO: Should we pull the current epoch from RPC or make it be a CLI argument?
-    let current_epoch: u16 = 840;
-    // TODO: Determine how this should be passed. The number of epochs to look back
-    let look_back_period = 50;
-    // TODO: Determine if this should be an argument
-    let number_of_validator_delegations = 200;
+    let current_epoch = CURRENT_EPOCH;
+    let look_back_period = LOOK_BACK_PERIOD;
+    let number_of_validator_delegations = NUMBER_OF_VALIDATOR_DELEGATIONS;
 
-    // Load existing steward config and overwrite parameters based on CLI args
-    let mut steward_config = fetch_config(&rpc_client).await?;
-    args.update_steward_config(&mut steward_config);
+    let steward_config = args.load_steward_config(rpc_client).await?;
+    let fee_model = args.load_fee_model(rpc_client, &steward_config).await?;
 
     let simulation_start_epoch = current_epoch.saturating_sub(look_back_period);
+    let parameter_schedule = if args.replay_historical_config {
+        args.historical_parameter_schedule(db_connection, &steward_config, current_epoch)
+            .await?
+    } else {
+        Vec::new()
+    };
+    let options = args.simulation_options(fee_model, parameter_schedule)?;
 
     let rebalancing_cycles = rebalancing_simulation(
         db_connection,
@@ -122,33 +330,367 @@ pub async fn handle_backtest(
         current_epoch,
         args.steward_cycle_rate,
         number_of_validator_delegations,
-        steward_config.parameters.instant_unstake_cap_bps,
-        steward_config.parameters.scoring_unstake_cap_bps,
-        std::cmp::max(
-            steward_config.parameters.mev_commission_range,
-            std::cmp::max(
-                steward_config.parameters.epoch_credits_range,
-                steward_config.parameters.commission_range,
-            ),
-        ),
+        options.clone(),
     )
     .await?;
 
     let aggregated_apy = calculate_aggregated_apy(&rebalancing_cycles, look_back_period)?;
 
+    if args.report_look_ahead_bias {
+        // Re-run the simulation in the other data access mode to measure how much the
+        // current epoch's unfinished data moves the result
+        let comparison_options = SimulationOptions {
+            point_in_time: !options.point_in_time,
+            ..options.clone()
+        };
+        let comparison_cycles = rebalancing_simulation(
+            db_connection,
+            &steward_config,
+            simulation_start_epoch,
+            current_epoch,
+            args.steward_cycle_rate,
+            number_of_validator_delegations,
+            comparison_options,
+        )
+        .await?;
+        let comparison_apy = calculate_aggregated_apy(&comparison_cycles, look_back_period)?;
+
+        let (point_in_time_apy, latest_apy) = if options.point_in_time {
+            (aggregated_apy, comparison_apy)
+        } else {
+            (comparison_apy, aggregated_apy)
+        };
+        info!(
+            "Point in time aggregated APY: {:.4}%",
+            point_in_time_apy * 100.0
+        );
+        info!("Latest data aggregated APY: {:.4}%", latest_apy * 100.0);
+        info!(
+            "Look-ahead bias: {:.4}%",
+            (latest_apy - point_in_time_apy) * 100.0
+        );
+    }
+
+    if args.report_reward_eligibility_bias {
+        // Re-run the simulation with the other reward eligibility to measure how much only
+        // rewarding active stake understates the APY
+        let comparison_options = SimulationOptions {
+            reward_eligibility: match options.reward_eligibility {
+                RewardEligibility::ActiveOnly => RewardEligibility::Effective,
+                RewardEligibility::Effective => RewardEligibility::ActiveOnly,
+            },
+            ..options.clone()
+        };
+        let comparison_cycles = rebalancing_simulation(
+            db_connection,
+            &steward_config,
+            simulation_start_epoch,
+            current_epoch,
+            args.steward_cycle_rate,
+            number_of_validator_delegations,
+            comparison_options,
+        )
+        .await?;
+        let comparison_apy = calculate_aggregated_apy(&comparison_cycles, look_back_period)?;
+
+        let (active_only_apy, effective_apy) = match options.reward_eligibility {
+            RewardEligibility::ActiveOnly => (aggregated_apy, comparison_apy),
+            RewardEligibility::Effective => (comparison_apy, aggregated_apy),
+        };
+        info!(
+            "Active stake rewards aggregated APY: {:.4}%",
+            active_only_apy * 100.0
+        );
+        info!(
+            "Effective stake rewards aggregated APY: {:.4}%",
+            effective_apy * 100.0
+        );
+        info!(
+            "Reward eligibility bias: {:.4}%",
+            (effective_apy - active_only_apy) * 100.0
+        );
+    }
+
     let stake_utilization_ratio =
         calculate_stake_utilization_rate(db_connection, look_back_period, current_epoch).await?;
 
     let final_apy = aggregated_apy * stake_utilization_ratio;
 
+    let net_aggregated_apy = calculate_net_aggregated_apy(&rebalancing_cycles, look_back_period)?;
+    let final_net_apy = net_aggregated_apy * stake_utilization_ratio;
+
+    report_fee_revenue(&rebalancing_cycles);
+    report_turnover(&rebalancing_cycles, args.crank_transaction_fee_lamports);
+    report_decentralization(&rebalancing_cycles);
+    report_rug_exposure(&rebalancing_cycles, &steward_config);
+
     info!("Rebalancing cycles completed: {}", rebalancing_cycles.len());
     info!("Raw aggregated APY: {:.4}%", aggregated_apy * 100.0);
+    info!(
+        "Raw net-to-holder aggregated APY: {:.4}%",
+        net_aggregated_apy * 100.0
+    );
     info!("Stake utilization ratio: {:.4}", stake_utilization_ratio);
     info!("Final adjusted APY: {:.4}%", final_apy * 100.0);
+    info!(
+        "Final adjusted net-to-holder APY: {:.4}%",
+        final_net_apy * 100.0
+    );
+
+    compare_with_realized_apy(
+        db_connection,
+        &rebalancing_cycles,
+        simulation_start_epoch,
+        current_epoch,
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Benchmarks the simulated exchange rate against what JitoSOL holders actually earned over the
+/// same window. Both APYs are annualized with the epochs' recorded start timestamps.
+pub async fn compare_with_realized_apy(
+    db_connection: &Pool<Postgres>,
+    rebalancing_cycles: &[RebalancingCycle],
+    start_epoch: u16,
+    end_epoch: u16,
+) -> Result<(), CliError> {
+    let realized_apy = StakePoolSnapshot::realized_holder_apy(
+        db_connection,
+        u64::from(start_epoch),
+        u64::from(end_epoch),
+    )
+    .await?;
+    let simulated_apy = match simulated_exchange_rate(rebalancing_cycles) {
+        Some(exchange_rate) => {
+            annualized_exchange_rate_change(
+                db_connection,
+                u64::from(start_epoch),
+                u64::from(end_epoch),
+                1.0,
+                exchange_rate,
+            )
+            .await?
+        }
+        None => None,
+    };
 
+    match (simulated_apy, realized_apy) {
+        (Some(simulated_apy), Some(realized_apy)) => {
+            info!("Simulated exchange rate APY: {:.4}%", simulated_apy * 100.0);
+            info!("Realized exchange rate APY: {:.4}%", realized_apy * 100.0);
+            info!(
+                "APY difference (simulated - realized): {:.4}%",
+                (simulated_apy - realized_apy) * 100.0
+            );
+        }
+        _ => info!(
+            "No stake pool snapshots or cluster history recorded for epochs {} and {}, skipping the realized APY comparison",
+            start_epoch, end_epoch
+        ),
+    }
     Ok(())
 }
 
+fn report_fee_revenue(rebalancing_cycles: &[RebalancingCycle]) {
+    let mut total_manager_revenue = 0u64;
+    let mut total_referral_revenue = 0u64;
+
+    for epoch_fees in rebalancing_cycles
+        .iter()
+        .flat_map(|cycle| &cycle.epoch_fees)
+    {
+        total_manager_revenue += epoch_fees.manager_revenue();
+        total_referral_revenue += epoch_fees.referral_fee;
+
+        info!(
+            "Epoch {} fee revenue: {:.6} SOL (epoch fee: {:.6} SOL on {:.6} SOL rewards, deposit fee: {:.6} SOL, withdrawal fee: {:.6} SOL, referrals: {:.6} SOL)",
+            epoch_fees.epoch,
+            epoch_fees.manager_revenue() as f64 / LAMPORTS_PER_SOL as f64,
+            epoch_fees.epoch_fee as f64 / LAMPORTS_PER_SOL as f64,
+            epoch_fees.gross_rewards as f64 / LAMPORTS_PER_SOL as f64,
+            epoch_fees.deposit_fee as f64 / LAMPORTS_PER_SOL as f64,
+            epoch_fees.withdrawal_fee as f64 / LAMPORTS_PER_SOL as f64,
+            epoch_fees.referral_fee as f64 / LAMPORTS_PER_SOL as f64
+        );
+    }
+
+    info!(
+        "Total fee revenue: {:.6} SOL to the manager, {:.6} SOL to referrers",
+        total_manager_revenue as f64 / LAMPORTS_PER_SOL as f64,
+        total_referral_revenue as f64 / LAMPORTS_PER_SOL as f64
+    );
+}
+
+fn report_turnover(rebalancing_cycles: &[RebalancingCycle], crank_transaction_fee_lamports: u64) {
+    let mut total_turnover = CycleTurnover::default();
+
+    for cycle in rebalancing_cycles {
+        let turnover = &cycle.turnover;
+        total_turnover += turnover;
+
+        info!(
+            "Cycle {}-{} turnover: {:.3} SOL deactivated (scoring: {:.3} SOL, instant unstake: {:.3} SOL, stake deposits: {:.3} SOL, rebalancing: {:.3} SOL), {} validators entered, {} exited, {} instantly unstaked, {:.6} SOL rewards forgone, {} crank transactions costing {:.6} SOL",
+            cycle.start_epoch,
+            cycle.end_epoch,
+            turnover.deactivated_lamports() as f64 / LAMPORTS_PER_SOL as f64,
+            turnover.scoring_deactivated_lamports as f64 / LAMPORTS_PER_SOL as f64,
+            turnover.instant_unstake_deactivated_lamports as f64 / LAMPORTS_PER_SOL as f64,
+            turnover.stake_deposit_deactivated_lamports as f64 / LAMPORTS_PER_SOL as f64,
+            turnover.rebalance_deactivated_lamports as f64 / LAMPORTS_PER_SOL as f64,
+            turnover.validators_entered,
+            turnover.validators_exited,
+            turnover.validators_instantly_unstaked,
+            turnover.forgone_rewards_lamports as f64 / LAMPORTS_PER_SOL as f64,
+            turnover.crank_transactions(),
+            turnover.estimated_crank_cost_lamports(crank_transaction_fee_lamports) as f64
+                / LAMPORTS_PER_SOL as f64
+        );
+    }
+
+    info!(
+        "Total turnover: {:.3} SOL deactivated (scoring: {:.3} SOL, instant unstake: {:.3} SOL, stake deposits: {:.3} SOL, rebalancing: {:.3} SOL), {} validators entered, {} exited, {} instantly unstaked",
+        total_turnover.deactivated_lamports() as f64 / LAMPORTS_PER_SOL as f64,
+        total_turnover.scoring_deactivated_lamports as f64 / LAMPORTS_PER_SOL as f64,
+        total_turnover.instant_unstake_deactivated_lamports as f64 / LAMPORTS_PER_SOL as f64,
+        total_turnover.stake_deposit_deactivated_lamports as f64 / LAMPORTS_PER_SOL as f64,
+        total_turnover.rebalance_deactivated_lamports as f64 / LAMPORTS_PER_SOL as f64,
+        total_turnover.validators_entered,
+        total_turnover.validators_exited,
+        total_turnover.validators_instantly_unstaked
+    );
+    info!(
+        "Total rewards forgone while stake was activating or deactivating: {:.6} SOL",
+        total_turnover.forgone_rewards_lamports as f64 / LAMPORTS_PER_SOL as f64
+    );
+    info!(
+        "Total crank transactions: {} ({} score, {} instant unstake checks, {} rebalances) costing {:.6} SOL",
+        total_turnover.crank_transactions(),
+        total_turnover.score_computations,
+        total_turnover.instant_unstake_checks,
+        total_turnover.stake_operations,
+        total_turnover.estimated_crank_cost_lamports(crank_transaction_fee_lamports) as f64
+            / LAMPORTS_PER_SOL as f64
+    );
+}
+
+fn report_rug_exposure(rebalancing_cycles: &[RebalancingCycle], steward_config: &Config) {
+    let exposures: Vec<&RugExposure> = rebalancing_cycles
+        .iter()
+        .flat_map(|cycle| &cycle.rug_exposures)
+        .collect();
+
+    for exposure in &exposures {
+        let removal = match exposure.epochs_to_removal() {
+            Some(epochs) if exposure.instantly_unstaked => {
+                format!("instantly unstaked after {} epochs", epochs)
+            }
+            Some(epochs) => format!("removed after {} epochs", epochs),
+            None => String::from("still delegated at the end of the simulation"),
+        };
+        info!(
+            "Commission rug at epoch {} by {}: commission {}% -> {}%, MEV commission {} bps -> {} bps, {:.3} SOL delegated, {}, {:.6} SOL rewards lost",
+            exposure.epoch,
+            exposure.vote_account,
+            exposure.commission_before,
+            exposure.commission_after,
+            exposure.mev_commission_bps_before,
+            exposure.mev_commission_bps_after,
+            exposure.delegated_lamports as f64 / LAMPORTS_PER_SOL as f64,
+            removal,
+            exposure.rewards_lost_lamports as f64 / LAMPORTS_PER_SOL as f64
+        );
+    }
+
+    let removal_epochs: Vec<u16> = exposures
+        .iter()
+        .filter_map(|exposure| exposure.epochs_to_removal())
+        .collect();
+    let average_epochs_to_removal = if removal_epochs.is_empty() {
+        0.0
+    } else {
+        removal_epochs
+            .iter()
+            .map(|epochs| f64::from(*epochs))
+            .sum::<f64>()
+            / removal_epochs.len() as f64
+    };
+    let parameters = &steward_config.parameters;
+    info!(
+        "Commission rug exposure (commission threshold: {}%, MEV commission threshold: {} bps, instant unstake cap: {} bps): {} rugs, {:.3} SOL delegated, {} instantly unstaked, {} still delegated, {:.2} epochs to removal on average, {:.6} SOL rewards lost",
+        parameters.commission_threshold,
+        parameters.mev_commission_bps_threshold,
+        parameters.instant_unstake_cap_bps,
+        exposures.len(),
+        exposures
+            .iter()
+            .map(|exposure| exposure.delegated_lamports)
+            .sum::<u64>() as f64
+            / LAMPORTS_PER_SOL as f64,
+        exposures
+            .iter()
+            .filter(|exposure| exposure.instantly_unstaked)
+            .count(),
+        exposures
+            .iter()
+            .filter(|exposure| exposure.is_open())
+            .count(),
+        average_epochs_to_removal,
+        exposures
+            .iter()
+            .map(|exposure| exposure.rewards_lost_lamports)
+            .sum::<u64>() as f64
+            / LAMPORTS_PER_SOL as f64
+    );
+}
+
+fn report_decentralization(rebalancing_cycles: &[RebalancingCycle]) {
+    let epochs: Vec<&EpochDecentralization> = rebalancing_cycles
+        .iter()
+        .flat_map(|cycle| &cycle.decentralization)
+        .collect();
+    if epochs.is_empty() {
+        return;
+    }
+
+    for decentralization in &epochs {
+        let client_type_mix = decentralization
+            .client_type_shares
+            .iter()
+            .map(|(client_type, share)| {
+                format!("{}: {:.2}%", client_type_name(*client_type), share * 100.0)
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        info!(
+            "Epoch {} decentralization: HHI {:.4}, superminority share {:.2}%, Nakamoto coefficient {} -> {} ({:+}), {} subnets (largest {:.2}%), clients: {}",
+            decentralization.epoch,
+            decentralization.pool_stake_hhi,
+            decentralization.superminority_share * 100.0,
+            decentralization.nakamoto_coefficient_without_pool,
+            decentralization.nakamoto_coefficient_with_pool,
+            decentralization.nakamoto_coefficient_change(),
+            decentralization.subnet_count,
+            decentralization.largest_subnet_share * 100.0,
+            client_type_mix
+        );
+    }
+
+    let number_of_epochs = epochs.len() as f64;
+    info!(
+        "Average decentralization: HHI {:.4}, superminority share {:.2}%, Nakamoto coefficient change {:+.2}, largest subnet share {:.2}%",
+        epochs.iter().map(|d| d.pool_stake_hhi).sum::<f64>() / number_of_epochs,
+        epochs.iter().map(|d| d.superminority_share).sum::<f64>() / number_of_epochs * 100.0,
+        epochs
+            .iter()
+            .map(|d| d.nakamoto_coefficient_change() as f64)
+            .sum::<f64>()
+            / number_of_epochs,
+        epochs.iter().map(|d| d.largest_subnet_share).sum::<f64>() / number_of_epochs * 100.0
+    );
+}
+
 pub async fn rebalancing_simulation(
     db_connection: &Pool<Postgres>,
     steward_config: &Config,
@@ -156,23 +698,51 @@ pub async fn rebalancing_simulation(
     simulation_end_epoch: u16,
     steward_cycle_rate: u16,
     number_of_validator_delegations: usize,
-    instant_unstake_cap_bps: u32,
-    scoring_unstake_cap_bps: u32,
-    validator_historical_start_offset: u16,
+    options: SimulationOptions,
 ) -> Result<Vec<RebalancingCycle>, CliError> {
-    let mut simulator = RebalancingSimulator::new(
+    let mut simulator = build_rebalancing_simulator(
         db_connection,
-        steward_config.clone(),
+        steward_config,
         simulation_start_epoch,
         simulation_end_epoch,
         steward_cycle_rate,
         number_of_validator_delegations,
-        instant_unstake_cap_bps,
-        scoring_unstake_cap_bps,
-        validator_historical_start_offset,
+        options,
     )
     .await?;
 
     // Run the simulation
     simulator.run_simulation(db_connection).await
 }
+
+/// Loads the simulator for the given epoch range, looking far enough back in the validator
+/// history for every scoring window of `steward_config`
+pub async fn build_rebalancing_simulator(
+    db_connection: &Pool<Postgres>,
+    steward_config: &Config,
+    simulation_start_epoch: u16,
+    simulation_end_epoch: u16,
+    steward_cycle_rate: u16,
+    number_of_validator_delegations: usize,
+    options: SimulationOptions,
+) -> Result<RebalancingSimulator, CliError> {
+    RebalancingSimulator::new(
+        db_connection,
+        steward_config.clone(),
+        simulation_start_epoch,
+        simulation_end_epoch,
+        steward_cycle_rate,
+        number_of_validator_delegations,
+        steward_config.parameters.instant_unstake_cap_bps,
+        steward_config.parameters.scoring_unstake_cap_bps,
+        std::cmp::max(
+            steward_config.parameters.mev_commission_range,
+            std::cmp::max(
+                steward_config.parameters.epoch_credits_range,
+                steward_config.parameters.commission_range,
+            ),
+        ),
+        options,
+    )
+    .await
+}
@@ -0,0 +1,119 @@
+use crate::{
+    commands::{BacktestArgs, NUMBER_OF_VALIDATOR_DELEGATIONS, build_rebalancing_simulator},
+    error::CliError,
+    utils::ScoreDiff,
+};
+use clap::Parser;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use sqlx::{Pool, Postgres};
+use stakenet_simulator_db::steward_validator_states::StewardValidatorState;
+use std::collections::HashMap;
+use tracing::info;
+
+#[derive(Clone, Debug, Parser)]
+pub struct DiffScoresArgs {
+    #[command(flatten)]
+    pub backtest_args: BacktestArgs,
+    #[arg(long, env)]
+    pub epoch: u16,
+    #[arg(long, env, default_value = "0.0001")]
+    pub score_tolerance: f64,
+    #[arg(long, env, default_value = "20")]
+    pub max_divergences_logged: usize,
+}
+
+/// Scores every validator at the start of the steward cycle beginning at `epoch` and compares
+/// the scores and yield scores with the ones the steward program computed on-chain
+pub async fn handle_diff_scores(
+    args: DiffScoresArgs,
+    db_connection: &Pool<Postgres>,
+    rpc_client: &RpcClient,
+) -> Result<(), CliError> {
+    let backtest_args = &args.backtest_args;
+    let epoch = args.epoch;
+
+    let recorded_states =
+        StewardValidatorState::fetch_for_cycle_start_epoch(db_connection, u64::from(epoch)).await?;
+    if recorded_states.is_empty() {
+        return Err(CliError::StewardStateNotFound(epoch));
+    }
+
+    let steward_config = backtest_args.load_steward_config(rpc_client).await?;
+    let fee_model = backtest_args
+        .load_fee_model(rpc_client, &steward_config)
+        .await?;
+    let parameter_schedule = if backtest_args.replay_historical_config {
+        backtest_args
+            .historical_parameter_schedule(db_connection, &steward_config, epoch)
+            .await?
+    } else {
+        Vec::new()
+    };
+    let options = backtest_args.simulation_options(fee_model, parameter_schedule)?;
+
+    let mut simulator = build_rebalancing_simulator(
+        db_connection,
+        &steward_config,
+        epoch,
+        epoch.saturating_add(1),
+        backtest_args.steward_cycle_rate,
+        NUMBER_OF_VALIDATOR_DELEGATIONS,
+        options,
+    )
+    .await?;
+    let simulated_scores = simulator.score_validators_at_epoch(epoch).await?;
+
+    let simulated_score: HashMap<String, f64> = simulated_scores
+        .iter()
+        .map(|validator| (validator.vote_account.clone(), validator.score))
+        .collect();
+    let simulated_yield_score: HashMap<String, f64> = simulated_scores
+        .iter()
+        .map(|validator| (validator.vote_account.clone(), validator.yield_score))
+        .collect();
+    let recorded_score: HashMap<String, f64> = recorded_states
+        .iter()
+        .map(|state| (state.vote_pubkey.clone(), state.score()))
+        .collect();
+    let recorded_yield_score: HashMap<String, f64> = recorded_states
+        .iter()
+        .map(|state| (state.vote_pubkey.clone(), state.yield_score()))
+        .collect();
+
+    for (name, diff) in [
+        (
+            "score",
+            ScoreDiff::new(&simulated_score, &recorded_score, args.score_tolerance),
+        ),
+        (
+            "yield score",
+            ScoreDiff::new(
+                &simulated_yield_score,
+                &recorded_yield_score,
+                args.score_tolerance,
+            ),
+        ),
+    ] {
+        info!(
+            "Epoch {} {}: {} validators compared, {} diverge by more than {}, {} not scored by the simulation",
+            epoch,
+            name,
+            diff.compared,
+            diff.divergences.len(),
+            args.score_tolerance,
+            diff.missing_from_simulation
+        );
+        info!(
+            "Epoch {} {}: max absolute difference {:.6}, mean absolute difference {:.6}",
+            epoch, name, diff.max_abs_difference, diff.mean_abs_difference
+        );
+        for divergence in diff.divergences.iter().take(args.max_divergences_logged) {
+            info!(
+                "{} {}: simulated {:.6}, on-chain {:.6}",
+                divergence.vote_account, name, divergence.simulated, divergence.recorded
+            );
+        }
+    }
+
+    Ok(())
+}
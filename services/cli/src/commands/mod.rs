@@ -1,3 +1,9 @@
 pub mod backtest;
+pub mod diff_scores;
+pub mod project;
+pub mod validate;
 
 pub use backtest::*;
+pub use diff_scores::*;
+pub use project::*;
+pub use validate::*;
@@ -0,0 +1,147 @@
+use crate::{
+    commands::{
+        BacktestArgs, CURRENT_EPOCH, NUMBER_OF_VALIDATOR_DELEGATIONS, build_rebalancing_simulator,
+    },
+    error::CliError,
+    utils::{
+        ApyDistribution, ProjectionModel, calculate_aggregated_apy, calculate_net_aggregated_apy,
+    },
+};
+use clap::Parser;
+use rand::{SeedableRng, rngs::StdRng};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use sqlx::{Pool, Postgres};
+use stakenet_simulator_db::{
+    cluster_history_entry::ClusterHistoryEntry, epoch_rewards::EpochRewards,
+    validator_history_entry::ValidatorHistoryEntry,
+};
+use std::collections::HashSet;
+use tracing::info;
+
+#[derive(Clone, Debug, Parser)]
+pub struct ProjectArgs {
+    #[command(flatten)]
+    pub backtest_args: BacktestArgs,
+    #[arg(long, env, default_value = "50")]
+    pub projection_epochs: u16,
+    #[arg(long, env, default_value = "20")]
+    pub projection_runs: usize,
+    #[arg(long, env, default_value = "50")]
+    pub fit_epochs: u16,
+    #[arg(long, env)]
+    pub projection_seed: Option<u64>,
+}
+
+/// Fits every validator's behaviour over the last `fit_epochs` epochs, then runs the simulation
+/// `projection_runs` times over `projection_epochs` synthetic epochs following the recorded data
+/// and reports the distribution of the projected APYs
+pub async fn handle_project(
+    args: ProjectArgs,
+    db_connection: &Pool<Postgres>,
+    rpc_client: &RpcClient,
+) -> Result<(), CliError> {
+    let backtest_args = &args.backtest_args;
+    let fit_start_epoch = CURRENT_EPOCH.saturating_sub(args.fit_epochs);
+    let fit_end_epoch = CURRENT_EPOCH.saturating_sub(1);
+    let projection_end_epoch = CURRENT_EPOCH.saturating_add(args.projection_epochs);
+
+    let steward_config = backtest_args.load_steward_config(rpc_client).await?;
+    let fee_model = backtest_args
+        .load_fee_model(rpc_client, &steward_config)
+        .await?;
+    let options = backtest_args.simulation_options(fee_model, Vec::new())?;
+
+    info!(
+        "Fitting validator behaviour over epochs {} to {}",
+        fit_start_epoch, fit_end_epoch
+    );
+    let entries = ValidatorHistoryEntry::fetch_all_records_between_epochs(
+        db_connection,
+        fit_start_epoch.into(),
+        fit_end_epoch.into(),
+    )
+    .await?;
+    let vote_accounts: Vec<String> = entries
+        .iter()
+        .map(|entry| entry.vote_pubkey.clone())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let rewards = EpochRewards::fetch_for_validators_and_epochs(
+        db_connection,
+        &vote_accounts,
+        fit_start_epoch.into(),
+        fit_end_epoch.into(),
+    )
+    .await?;
+    let cluster_history_entries: Vec<ClusterHistoryEntry> =
+        ClusterHistoryEntry::fetch_all(db_connection)
+            .await?
+            .into_iter()
+            .filter(|entry| entry.epoch >= fit_start_epoch && entry.epoch <= fit_end_epoch)
+            .collect();
+    let model = ProjectionModel::fit(
+        &entries,
+        &rewards,
+        &cluster_history_entries,
+        steward_config
+            .parameters
+            .scoring_delinquency_threshold_ratio,
+    )
+    .ok_or(CliError::ProjectionDataNotFound(
+        fit_start_epoch,
+        fit_end_epoch,
+    ))?;
+    info!("Fitted {} validator profiles", model.profiles.len());
+
+    let mut rng = match args.projection_seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_os_rng(),
+    };
+    let base_simulator = build_rebalancing_simulator(
+        db_connection,
+        &steward_config,
+        CURRENT_EPOCH,
+        projection_end_epoch,
+        backtest_args.steward_cycle_rate,
+        NUMBER_OF_VALIDATOR_DELEGATIONS,
+        options,
+    )
+    .await?;
+    let mut gross_apys = Vec::with_capacity(args.projection_runs);
+    let mut net_apys = Vec::with_capacity(args.projection_runs);
+    for run in 0..args.projection_runs {
+        let synthetic = model.generate(CURRENT_EPOCH, args.projection_epochs, &mut rng);
+        let mut simulator = base_simulator.clone();
+        simulator.add_synthetic_epochs(synthetic);
+        let cycles = simulator.run_simulation(db_connection).await?;
+
+        let gross_apy = calculate_aggregated_apy(&cycles, args.projection_epochs)?;
+        let net_apy = calculate_net_aggregated_apy(&cycles, args.projection_epochs)?;
+        info!(
+            "Projection run {}: APY {:.4}%, net-to-holder APY {:.4}%",
+            run + 1,
+            gross_apy * 100.0,
+            net_apy * 100.0
+        );
+        gross_apys.push(gross_apy);
+        net_apys.push(net_apy);
+    }
+
+    for (name, apys) in [("APY", gross_apys), ("net-to-holder APY", net_apys)] {
+        let distribution = ApyDistribution::new(apys);
+        info!(
+            "Projected {} over {} epochs: mean {:.4}%, p5 {:.4}%, p25 {:.4}%, p50 {:.4}%, p75 {:.4}%, p95 {:.4}%",
+            name,
+            args.projection_epochs,
+            distribution.mean() * 100.0,
+            distribution.percentile(5.0) * 100.0,
+            distribution.percentile(25.0) * 100.0,
+            distribution.percentile(50.0) * 100.0,
+            distribution.percentile(75.0) * 100.0,
+            distribution.percentile(95.0) * 100.0
+        );
+    }
+
+    Ok(())
+}
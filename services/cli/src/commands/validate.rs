@@ -0,0 +1,129 @@
+use crate::{
+    commands::{
+        BacktestArgs, CURRENT_EPOCH, LOOK_BACK_PERIOD, NUMBER_OF_VALIDATOR_DELEGATIONS,
+        compare_with_realized_apy, rebalancing_simulation,
+    },
+    error::CliError,
+    utils::{CycleFidelity, calculate_net_aggregated_apy},
+};
+use clap::Parser;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use sqlx::{Pool, Postgres};
+use stakenet_simulator_db::jito_sol_validator_stakes::JitoSolValidatorStake;
+use std::collections::HashMap;
+use tracing::info;
+
+#[derive(Clone, Debug, Parser)]
+pub struct ValidateArgs {
+    #[command(flatten)]
+    pub backtest_args: BacktestArgs,
+    #[arg(long, env, default_value = "100")]
+    pub min_delegation_sol: u64,
+}
+
+/// Runs the simulation with the historical steward config and compares every cycle with the
+/// JitoSOL pool's actual allocation, and the simulated APY with the realized exchange rate APY
+pub async fn handle_validate(
+    args: ValidateArgs,
+    db_connection: &Pool<Postgres>,
+    rpc_client: &RpcClient,
+) -> Result<(), CliError> {
+    let backtest_args = &args.backtest_args;
+    let current_epoch = CURRENT_EPOCH;
+    let look_back_period = LOOK_BACK_PERIOD;
+    let simulation_start_epoch = current_epoch.saturating_sub(look_back_period);
+
+    let steward_config = backtest_args.load_steward_config(rpc_client).await?;
+    let fee_model = backtest_args
+        .load_fee_model(rpc_client, &steward_config)
+        .await?;
+    let parameter_schedule = backtest_args
+        .historical_parameter_schedule(db_connection, &steward_config, current_epoch)
+        .await?;
+    let options = backtest_args.simulation_options(fee_model, parameter_schedule)?;
+
+    let rebalancing_cycles = rebalancing_simulation(
+        db_connection,
+        &steward_config,
+        simulation_start_epoch,
+        current_epoch,
+        backtest_args.steward_cycle_rate,
+        NUMBER_OF_VALIDATOR_DELEGATIONS,
+        options,
+    )
+    .await?;
+
+    let min_delegation_lamports = args.min_delegation_sol.saturating_mul(LAMPORTS_PER_SOL);
+    let mut cycle_fidelities = Vec::new();
+    for cycle in &rebalancing_cycles {
+        // The pool's stake at the end of the cycle reflects the delegation decisions made at
+        // its start
+        let actual_stakes: HashMap<String, u64> =
+            JitoSolValidatorStake::fetch_for_epoch(db_connection, u64::from(cycle.end_epoch))
+                .await?
+                .into_iter()
+                .map(|stake| {
+                    let total = stake.total_stake_lamports();
+                    (stake.vote_pubkey, total)
+                })
+                .collect();
+        if actual_stakes.is_empty() {
+            info!(
+                "No JitoSOL validator list recorded for epoch {}, skipping cycle",
+                cycle.end_epoch
+            );
+            continue;
+        }
+
+        let fidelity = CycleFidelity::new(
+            cycle.start_epoch,
+            cycle.end_epoch,
+            &cycle.validator_targets,
+            &actual_stakes,
+            min_delegation_lamports,
+        );
+        info!(
+            "Cycle {}-{}: precision {:.4}, recall {:.4}, stake-weighted overlap {:.4}",
+            fidelity.start_epoch,
+            fidelity.end_epoch,
+            fidelity.precision,
+            fidelity.recall,
+            fidelity.stake_weighted_overlap
+        );
+        cycle_fidelities.push(fidelity);
+    }
+
+    if cycle_fidelities.is_empty() {
+        return Err(CliError::ValidatorListNotFound(
+            simulation_start_epoch,
+            current_epoch,
+        ));
+    }
+    let count = cycle_fidelities.len() as f64;
+    let mean =
+        |value: fn(&CycleFidelity) -> f64| cycle_fidelities.iter().map(value).sum::<f64>() / count;
+    info!(
+        "Compared {} of {} cycles with the pool's allocation",
+        cycle_fidelities.len(),
+        rebalancing_cycles.len()
+    );
+    info!("Mean precision: {:.4}", mean(|f| f.precision));
+    info!("Mean recall: {:.4}", mean(|f| f.recall));
+    info!(
+        "Fidelity score (mean stake-weighted overlap): {:.4}",
+        mean(|f| f.stake_weighted_overlap)
+    );
+
+    let simulated_apy = calculate_net_aggregated_apy(&rebalancing_cycles, look_back_period)?;
+    info!("Simulated net-to-holder APY: {:.4}%", simulated_apy * 100.0);
+    compare_with_realized_apy(
+        db_connection,
+        &rebalancing_cycles,
+        simulation_start_epoch,
+        current_epoch,
+    )
+    .await?;
+
+    Ok(())
+}
@@ -14,6 +14,9 @@ pub enum CliError {
     #[error("AnchorDeserializeError")]
     AnchorDeserializeError,
 
+    #[error("Unable to deserialize stake pool account")]
+    StakePoolDeserializeError,
+
     #[error("ArithmeticError")]
     ArithmeticError,
 
@@ -31,6 +34,58 @@ pub enum CliError {
         inactive_count: i64,
     },
 
+    #[error("No JitoSOL active stake recorded at or after epoch {0}")]
+    PoolTvlNotFound(u16),
+
+    #[error("IoError: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Invalid validator override entry: {0}")]
+    InvalidValidatorOverride(String),
+
+    #[error("No steward state recorded for the cycle starting at epoch {0}")]
+    StewardStateNotFound(u16),
+
+    #[error(
+        "No JitoSOL validator list recorded at the end of any cycle between epochs {0} and {1}, run fetch-jito-sol-validator-list"
+    )]
+    ValidatorListNotFound(u16, u16),
+
+    #[error(
+        "No steward config recorded at or before epoch {0}, run fetch-steward-config or start the simulation later"
+    )]
+    ParameterScheduleNotFound(u16),
+
+    #[error(
+        "Lamport conservation violated at epoch {epoch}: ledger expects {expected} lamports, pool holds {actual}"
+    )]
+    LamportConservationViolation {
+        epoch: u16,
+        expected: i128,
+        actual: u64,
+    },
+
+    #[error("Rounded {rounding} lamports at epoch {epoch}, more than the {max} allowed")]
+    RoundingOutOfBounds {
+        epoch: u16,
+        rounding: i128,
+        max: u64,
+    },
+
+    #[error("Invalid stake amount: {0}")]
+    InvalidStakeAmount(String),
+
+    #[error("Invalid scenario entry: {0}")]
+    InvalidScenario(String),
+
+    #[error(
+        "No cluster history with recorded epoch start timestamps between epochs {0} and {1} to fit the projection on"
+    )]
+    ProjectionDataNotFound(u16, u16),
+
+    #[error("The constant inflation curve requires --inflation-constant-rate")]
+    MissingInflationConstantRate,
+
     #[error("Unable to fetch top validators")]
     TopValidatorsNotFound,
 
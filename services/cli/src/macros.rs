@@ -1,8 +1,11 @@
 #[macro_export]
 macro_rules! modify_config_parameter_from_args {
     ($args:expr, $config:expr, $field:ident) => {
+        $crate::modify_config_parameter_from_args!($args, $config.parameters => $field)
+    };
+    ($args:expr, $target:expr => $field:ident) => {
         if let Some(value) = $args.$field {
-            $config.parameters.$field = value;
+            $target.$field = value;
         }
     };
 }
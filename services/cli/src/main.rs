@@ -1,6 +1,6 @@
 use crate::error::CliError;
 use clap::{Parser, Subcommand};
-use commands::backtest::*;
+use commands::{backtest::*, diff_scores::*, project::*, validate::*};
 use solana_client::nonblocking::rpc_client::RpcClient;
 use sqlx::postgres::PgPoolOptions;
 use std::sync::Arc;
@@ -36,6 +36,18 @@ pub enum Commands {
         #[command(flatten)]
         args: BacktestArgs,
     },
+    Validate {
+        #[command(flatten)]
+        args: ValidateArgs,
+    },
+    DiffScores {
+        #[command(flatten)]
+        args: DiffScoresArgs,
+    },
+    Project {
+        #[command(flatten)]
+        args: ProjectArgs,
+    },
 }
 
 #[tokio::main]
@@ -71,5 +83,23 @@ async fn main() -> Result<(), CliError> {
 
             handle_backtest(args, &db_conn_pool, &rpc_client).await
         }
+        Commands::Validate { args } => {
+            let rpc_url = cli.rpc_url.as_ref().ok_or(CliError::InvalidRPCUrl)?;
+            let rpc_client = RpcClient::new(rpc_url.to_string());
+
+            handle_validate(args, &db_conn_pool, &rpc_client).await
+        }
+        Commands::DiffScores { args } => {
+            let rpc_url = cli.rpc_url.as_ref().ok_or(CliError::InvalidRPCUrl)?;
+            let rpc_client = RpcClient::new(rpc_url.to_string());
+
+            handle_diff_scores(args, &db_conn_pool, &rpc_client).await
+        }
+        Commands::Project { args } => {
+            let rpc_url = cli.rpc_url.as_ref().ok_or(CliError::InvalidRPCUrl)?;
+            let rpc_client = RpcClient::new(rpc_url.to_string());
+
+            handle_project(args, &db_conn_pool, &rpc_client).await
+        }
     }
 }
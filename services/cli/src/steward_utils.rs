@@ -1,8 +1,10 @@
 use anchor_lang::AccountDeserialize;
 use jito_steward::Config;
 use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::borsh1::try_from_slice_unchecked;
 use solana_sdk::pubkey;
 use solana_sdk::pubkey::Pubkey;
+use spl_stake_pool::state::StakePool;
 
 use crate::error::CliError;
 
@@ -13,3 +15,12 @@ pub async fn fetch_config(rpc_client: &RpcClient) -> Result<Config, CliError> {
     let mut data: &[u8] = &account.data;
     Ok(Config::try_deserialize(&mut data).map_err(|_| CliError::AnchorDeserializeError)?)
 }
+
+pub async fn fetch_stake_pool(
+    rpc_client: &RpcClient,
+    stake_pool_address: &Pubkey,
+) -> Result<StakePool, CliError> {
+    let account = rpc_client.get_account(stake_pool_address).await?;
+    try_from_slice_unchecked::<StakePool>(&account.data)
+        .map_err(|_| CliError::StakePoolDeserializeError)
+}
@@ -0,0 +1,244 @@
+use crate::utils::ValidatorWithScore;
+use clap::ValueEnum;
+use std::collections::HashMap;
+
+/// How the pool's stake is split across the selected validators
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum AllocationMode {
+    /// The same stake on every validator, as the steward delegates
+    #[default]
+    EqualWeight,
+    /// Stake proportional to each validator's score
+    ScoreProportional,
+    /// Stake proportional to each validator's yield score
+    YieldScoreProportional,
+    /// Maximize the expected yield: fill the validators with the highest yield score up to the
+    /// per-validator cap, moving at most the turnover budget of stake per cycle. Without a cap
+    /// all of the stake goes to the highest yield validator.
+    Optimized,
+}
+
+impl AllocationMode {
+    /// Whether validators can be targeted below the stake they already hold
+    pub fn reduces_stake(&self) -> bool {
+        !matches!(self, Self::EqualWeight)
+    }
+}
+
+/// Target stake of every validator in `validators`.
+///
+/// `current_stakes` is the stake each validator is delegated before the allocation, `cap` the
+/// most a single validator can be delegated and `turnover_budget` the most stake that can be
+/// taken off the validators. Lamports that can't be allocated under the cap stay in the reserve.
+pub fn allocation_targets(
+    mode: AllocationMode,
+    validators: &[ValidatorWithScore],
+    current_stakes: &HashMap<String, u64>,
+    target_total: u64,
+    cap: Option<u64>,
+    turnover_budget: Option<u64>,
+) -> HashMap<String, u64> {
+    if validators.is_empty() {
+        return HashMap::new();
+    }
+
+    match mode {
+        AllocationMode::EqualWeight => {
+            let mut stake_per_validator = target_total / validators.len() as u64;
+            if let Some(cap) = cap {
+                stake_per_validator = stake_per_validator.min(cap);
+            }
+            validators
+                .iter()
+                .map(|validator| (validator.vote_account.clone(), stake_per_validator))
+                .collect()
+        }
+        AllocationMode::ScoreProportional => proportional_targets(
+            validators
+                .iter()
+                .map(|validator| (validator.vote_account.as_str(), validator.score)),
+            target_total,
+            cap,
+        ),
+        AllocationMode::YieldScoreProportional => proportional_targets(
+            validators
+                .iter()
+                .map(|validator| (validator.vote_account.as_str(), validator.yield_score)),
+            target_total,
+            cap,
+        ),
+        AllocationMode::Optimized => optimized_targets(
+            validators,
+            current_stakes,
+            target_total,
+            cap.unwrap_or(target_total),
+            turnover_budget.unwrap_or(u64::MAX),
+        ),
+    }
+}
+
+/// Splits `target_total` proportionally to the weights. Validators whose share exceeds the cap
+/// are held at the cap and the excess is split over the others.
+fn proportional_targets<'a>(
+    weights: impl Iterator<Item = (&'a str, f64)>,
+    target_total: u64,
+    cap: Option<u64>,
+) -> HashMap<String, u64> {
+    let mut uncapped: Vec<(&str, f64)> = weights
+        .map(|(vote_account, weight)| (vote_account, weight.max(0.0)))
+        .collect();
+    let mut targets: HashMap<String, u64> = uncapped
+        .iter()
+        .map(|(vote_account, _)| (vote_account.to_string(), 0))
+        .collect();
+    let mut remaining = target_total;
+
+    loop {
+        let weight_sum: f64 = uncapped.iter().map(|(_, weight)| weight).sum();
+        if weight_sum <= 0.0 {
+            break;
+        }
+        let share = |weight: f64| (remaining as f64 * weight / weight_sum) as u64;
+
+        let (capped, rest): (Vec<(&str, f64)>, Vec<(&str, f64)>) = uncapped
+            .into_iter()
+            .partition(|(_, weight)| cap.is_some_and(|cap| share(*weight) > cap));
+        let Some(cap) = cap.filter(|_| !capped.is_empty()) else {
+            for (vote_account, weight) in rest {
+                targets.insert(vote_account.to_string(), share(weight));
+            }
+            break;
+        };
+
+        for (vote_account, _) in capped {
+            targets.insert(vote_account.to_string(), cap);
+            remaining = remaining.saturating_sub(cap);
+        }
+        uncapped = rest;
+    }
+
+    targets
+}
+
+/// Keeps the stake validators already hold, fills the highest yield validators up to the cap
+/// with the lamports that aren't delegated, then moves stake from the lowest to the highest
+/// yield validators until the turnover budget runs out
+fn optimized_targets(
+    validators: &[ValidatorWithScore],
+    current_stakes: &HashMap<String, u64>,
+    target_total: u64,
+    cap: u64,
+    turnover_budget: u64,
+) -> HashMap<String, u64> {
+    let mut by_yield: Vec<&ValidatorWithScore> = validators.iter().collect();
+    by_yield.sort_by(|a, b| b.yield_score.total_cmp(&a.yield_score));
+
+    let mut targets: Vec<u64> = by_yield
+        .iter()
+        .map(|validator| {
+            current_stakes
+                .get(&validator.vote_account)
+                .copied()
+                .unwrap_or(0)
+                .min(cap)
+        })
+        .collect();
+
+    // Stake above the cap has to move and uses up the budget first
+    let forced_turnover: u64 = by_yield
+        .iter()
+        .zip(&targets)
+        .map(|(validator, target)| {
+            current_stakes
+                .get(&validator.vote_account)
+                .copied()
+                .unwrap_or(0)
+                - target
+        })
+        .sum();
+
+    let mut free = target_total.saturating_sub(targets.iter().sum());
+    for target in targets.iter_mut() {
+        let added = (cap - *target).min(free);
+        *target += added;
+        free -= added;
+    }
+
+    let mut budget = turnover_budget.saturating_sub(forced_turnover);
+    let (mut high, mut low) = (0, by_yield.len() - 1);
+    while high < low && budget > 0 {
+        if by_yield[high].yield_score <= by_yield[low].yield_score {
+            break;
+        }
+        let room = cap - targets[high];
+        if room == 0 {
+            high += 1;
+            continue;
+        }
+        if targets[low] == 0 {
+            low -= 1;
+            continue;
+        }
+        let moved = room.min(targets[low]).min(budget);
+        targets[high] += moved;
+        targets[low] -= moved;
+        budget -= moved;
+    }
+
+    by_yield
+        .iter()
+        .zip(targets)
+        .map(|(validator, target)| (validator.vote_account.clone(), target))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validator(vote_account: &str, score: f64, yield_score: f64) -> ValidatorWithScore {
+        ValidatorWithScore {
+            vote_account: vote_account.to_string(),
+            score,
+            yield_score,
+        }
+    }
+
+    #[test]
+    fn test_proportional_targets_redistribute_above_the_cap() {
+        let validators = vec![
+            validator("a", 0.8, 0.0),
+            validator("b", 0.1, 0.0),
+            validator("c", 0.1, 0.0),
+        ];
+
+        let targets = allocation_targets(
+            AllocationMode::ScoreProportional,
+            &validators,
+            &HashMap::new(),
+            1_000,
+            Some(500),
+            None,
+        );
+        assert_eq!(targets["a"], 500);
+        assert_eq!(targets["b"], 250);
+        assert_eq!(targets["c"], 250);
+    }
+
+    #[test]
+    fn test_optimized_targets_respect_the_turnover_budget() {
+        let validators = vec![validator("a", 1.0, 0.9), validator("b", 1.0, 0.5)];
+        let current_stakes = HashMap::from([(String::from("a"), 0), (String::from("b"), 1_000)]);
+
+        let targets = allocation_targets(
+            AllocationMode::Optimized,
+            &validators,
+            &current_stakes,
+            1_000,
+            Some(800),
+            Some(300),
+        );
+        assert_eq!(targets["a"], 300);
+        assert_eq!(targets["b"], 700);
+    }
+}
@@ -25,17 +25,66 @@ pub fn calculate_aggregated_apy(
         .ok_or(CliError::ArithmeticError)?
         .ending_total_lamports;
 
+    annualize_return(
+        initial_total_stake,
+        final_total_stake,
+        total_lookback_period,
+    )
+}
+
+/// The holders' lamports per pool token at the end of the simulation. The pool is seeded at one
+/// pool token per lamport, so this is the growth of the exchange rate net of the pool's fees.
+pub fn simulated_exchange_rate(rebalancing_cycles: &[RebalancingCycle]) -> Option<f64> {
+    let last_cycle = rebalancing_cycles.last()?;
+    if last_cycle.ending_holder_pool_tokens == 0 {
+        return None;
+    }
+    Some(
+        last_cycle.ending_holder_lamports.to_f64()?
+            / last_cycle.ending_holder_pool_tokens.to_f64()?,
+    )
+}
+
+/// APY of the pool token holders, from the growth of the simulated exchange rate net of the
+/// pool's fees. Deposits and withdrawals mint and burn pool tokens at the exchange rate, so unlike
+/// `calculate_aggregated_apy` they don't count as returns.
+pub fn calculate_net_aggregated_apy(
+    rebalancing_cycles: &[RebalancingCycle],
+    total_lookback_period: u16,
+) -> Result<f64, CliError> {
+    let Some(exchange_rate) = simulated_exchange_rate(rebalancing_cycles) else {
+        return Ok(0.0);
+    };
+
+    annualize_return_rate(exchange_rate - 1.0, total_lookback_period)
+}
+
+fn annualize_return(
+    initial_total_stake: u64,
+    final_total_stake: u64,
+    total_lookback_period: u16,
+) -> Result<f64, CliError> {
     if initial_total_stake == 0 {
         return Ok(0.0);
     }
 
-    let overall_return_rate = (final_total_stake - initial_total_stake)
+    let overall_return_rate = (final_total_stake
         .to_f64()
         .ok_or(CliError::ArithmeticError)?
+        - initial_total_stake
+            .to_f64()
+            .ok_or(CliError::ArithmeticError)?)
         / initial_total_stake
             .to_f64()
             .ok_or(CliError::ArithmeticError)?;
 
+    annualize_return_rate(overall_return_rate, total_lookback_period)
+}
+
+fn annualize_return_rate(
+    overall_return_rate: f64,
+    total_lookback_period: u16,
+) -> Result<f64, CliError> {
     // Convert to APY
     let lookback_period_in_days = total_lookback_period
         .to_f64()
@@ -118,6 +167,22 @@ mod tests {
         assert!((apy - 36.113).abs() < 0.001, "APY calculation is incorrect");
     }
 
+    #[test]
+    fn test_net_aggregated_apy_is_below_gross() {
+        let cycles = vec![RebalancingCycle {
+            starting_total_lamports: 1_000_000_000,
+            ending_total_lamports: 1_010_000_000,
+            ending_holder_lamports: 1_009_000_000,
+            ending_holder_pool_tokens: 1_000_000_000,
+            ..Default::default()
+        }];
+
+        let gross = calculate_aggregated_apy(&cycles, 10).unwrap();
+        let net = calculate_net_aggregated_apy(&cycles, 10).unwrap();
+        assert!((gross - calculate_apy(0.01, 20.0, DAYS_PER_YEAR)).abs() < 1e-12);
+        assert!((net - calculate_apy(0.009, 20.0, DAYS_PER_YEAR)).abs() < 1e-12);
+    }
+
     #[test]
     fn test_calculate_stake_utilization_rate_from_balances() {
         // INACTIVE BALANCE is 0
@@ -0,0 +1,147 @@
+use std::collections::{BTreeMap, HashMap};
+
+/// A validator's stake and attributes at an epoch, as used for the decentralization metrics
+#[derive(Clone, Debug, Default)]
+pub struct ValidatorStakeSnapshot {
+    /// Stake delegated to the validator by the simulated pool
+    pub pool_stake: u64,
+    /// The validator's activated stake excluding the stake the pool actually delegated to it
+    pub network_stake: u64,
+    pub is_superminority: bool,
+    pub client_type: u8,
+    pub ip: [u8; 4],
+}
+
+/// Decentralization and concentration of the simulated delegation at the end of an epoch
+#[derive(Clone, Debug, Default)]
+pub struct EpochDecentralization {
+    pub epoch: u16,
+    /// Herfindahl-Hirschman index of the pool's stake across validators, from 0 to 1
+    pub pool_stake_hhi: f64,
+    /// Share of the pool's stake delegated to superminority validators
+    pub superminority_share: f64,
+    /// Fewest validators holding more than a third of the network stake without the pool
+    pub nakamoto_coefficient_without_pool: usize,
+    /// Fewest validators holding more than a third of the network stake with the simulated
+    /// pool's stake added
+    pub nakamoto_coefficient_with_pool: usize,
+    /// Share of the pool's stake by validator client type
+    pub client_type_shares: BTreeMap<u8, f64>,
+    /// Number of IP /16 subnets the pool's stake is spread over
+    pub subnet_count: usize,
+    /// Share of the pool's stake in the /16 subnet holding the most of it
+    pub largest_subnet_share: f64,
+}
+
+impl EpochDecentralization {
+    pub fn compute(epoch: u16, validators: &[ValidatorStakeSnapshot]) -> Self {
+        let pool_stake: u64 = validators.iter().map(|v| v.pool_stake).sum();
+        let share = |stake: u64| {
+            if pool_stake == 0 {
+                0.0
+            } else {
+                stake as f64 / pool_stake as f64
+            }
+        };
+
+        let pool_stake_hhi = validators.iter().map(|v| share(v.pool_stake).powi(2)).sum();
+        let superminority_share = share(
+            validators
+                .iter()
+                .filter(|v| v.is_superminority)
+                .map(|v| v.pool_stake)
+                .sum(),
+        );
+
+        let mut client_type_stakes: BTreeMap<u8, u64> = BTreeMap::new();
+        let mut subnet_stakes: HashMap<[u8; 2], u64> = HashMap::new();
+        for validator in validators.iter().filter(|v| v.pool_stake > 0) {
+            *client_type_stakes.entry(validator.client_type).or_default() += validator.pool_stake;
+            *subnet_stakes
+                .entry([validator.ip[0], validator.ip[1]])
+                .or_default() += validator.pool_stake;
+        }
+
+        Self {
+            epoch,
+            pool_stake_hhi,
+            superminority_share,
+            nakamoto_coefficient_without_pool: nakamoto_coefficient(
+                validators.iter().map(|v| v.network_stake),
+            ),
+            nakamoto_coefficient_with_pool: nakamoto_coefficient(
+                validators.iter().map(|v| v.network_stake + v.pool_stake),
+            ),
+            client_type_shares: client_type_stakes
+                .into_iter()
+                .map(|(client_type, stake)| (client_type, share(stake)))
+                .collect(),
+            subnet_count: subnet_stakes.len(),
+            largest_subnet_share: share(subnet_stakes.values().copied().max().unwrap_or(0)),
+        }
+    }
+
+    /// Change of the network Nakamoto coefficient caused by the pool's stake. Positive when the
+    /// pool's delegation makes the network harder to halt.
+    pub fn nakamoto_coefficient_change(&self) -> i64 {
+        self.nakamoto_coefficient_with_pool as i64 - self.nakamoto_coefficient_without_pool as i64
+    }
+}
+
+/// Fewest validators that together hold more than a third of the stake
+pub fn nakamoto_coefficient(stakes: impl Iterator<Item = u64>) -> usize {
+    let mut stakes: Vec<u64> = stakes.filter(|stake| *stake > 0).collect();
+    stakes.sort_unstable_by(|a, b| b.cmp(a));
+    let total: u128 = stakes.iter().map(|stake| u128::from(*stake)).sum();
+
+    let mut cumulative = 0u128;
+    for (index, stake) in stakes.iter().enumerate() {
+        cumulative += u128::from(*stake);
+        if cumulative * 3 > total {
+            return index + 1;
+        }
+    }
+    0
+}
+
+pub fn client_type_name(client_type: u8) -> String {
+    match client_type {
+        0 => String::from("Solana Labs"),
+        1 => String::from("Jito Labs"),
+        2 => String::from("Firedancer"),
+        3 => String::from("Agave"),
+        other => format!("client type {}", other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(pool_stake: u64, network_stake: u64, ip: [u8; 4]) -> ValidatorStakeSnapshot {
+        ValidatorStakeSnapshot {
+            pool_stake,
+            network_stake,
+            ip,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_pool_stake_on_small_validators_raises_nakamoto_coefficient() {
+        let validators = vec![
+            snapshot(0, 40, [10, 0, 0, 1]),
+            snapshot(0, 30, [10, 1, 0, 1]),
+            snapshot(20, 15, [10, 2, 0, 1]),
+            snapshot(20, 15, [10, 2, 0, 2]),
+        ];
+
+        let decentralization = EpochDecentralization::compute(800, &validators);
+        assert_eq!(decentralization.nakamoto_coefficient_without_pool, 1);
+        assert_eq!(decentralization.nakamoto_coefficient_with_pool, 2);
+        assert_eq!(decentralization.nakamoto_coefficient_change(), 1);
+        assert!((decentralization.pool_stake_hhi - 0.5).abs() < 1e-12);
+        assert_eq!(decentralization.subnet_count, 1);
+        assert!((decentralization.largest_subnet_share - 1.0).abs() < 1e-12);
+    }
+}
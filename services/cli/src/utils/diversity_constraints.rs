@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+use validator_history::ValidatorHistoryEntry as JitoValidatorHistoryEntry;
+
+const MAX_BPS: u64 = 10_000;
+
+/// Limits on how concentrated the selected validator set can be
+#[derive(Clone, Debug, Default)]
+pub struct DiversityConstraints {
+    /// Maximum share of the selected validators running the same client type, in bps
+    pub max_client_type_share_bps: Option<u16>,
+    /// Maximum number of selected validators in the same IP /16 subnet
+    pub max_validators_per_subnet: Option<usize>,
+    /// Leave superminority validators out of the selection
+    pub exclude_superminority: bool,
+    /// Maximum stake delegated to a single validator as a share of the pool, in bps
+    pub max_validator_stake_bps: Option<u16>,
+}
+
+/// The attributes of a validator the constraints are checked against
+#[derive(Clone, Copy, Debug)]
+pub struct ValidatorAttributes {
+    pub client_type: u8,
+    pub subnet: [u8; 2],
+    pub is_superminority: bool,
+}
+
+impl From<&JitoValidatorHistoryEntry> for ValidatorAttributes {
+    fn from(entry: &JitoValidatorHistoryEntry) -> Self {
+        Self {
+            client_type: entry.client_type,
+            subnet: [entry.ip[0], entry.ip[1]],
+            is_superminority: entry.is_superminority == 1,
+        }
+    }
+}
+
+/// A constraint that kept a validator out of the selection
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiversityConstraint {
+    ClientType,
+    Subnet,
+    Superminority,
+    /// The validator has no history entry to check the constraints against
+    UnknownAttributes,
+}
+
+impl DiversityConstraints {
+    /// Whether the constraints depend on the validators' attributes
+    pub fn uses_attributes(&self) -> bool {
+        self.max_client_type_share_bps.is_some()
+            || self.max_validators_per_subnet.is_some()
+            || self.exclude_superminority
+    }
+
+    /// Most lamports a single validator can be delegated out of `pool_lamports`
+    pub fn validator_stake_cap(&self, pool_lamports: u64) -> Option<u64> {
+        self.max_validator_stake_bps
+            .map(|bps| (u128::from(pool_lamports) * u128::from(bps) / u128::from(MAX_BPS)) as u64)
+    }
+}
+
+/// Tracks the validators selected so far against the constraints
+pub struct DiversitySelection<'a> {
+    constraints: &'a DiversityConstraints,
+    max_validators_per_client_type: Option<usize>,
+    client_type_counts: HashMap<u8, usize>,
+    subnet_counts: HashMap<[u8; 2], usize>,
+}
+
+impl<'a> DiversitySelection<'a> {
+    pub fn new(constraints: &'a DiversityConstraints, number_of_validators: usize) -> Self {
+        Self {
+            constraints,
+            // Always allow at least one validator of each client type
+            max_validators_per_client_type: constraints.max_client_type_share_bps.map(|bps| {
+                (number_of_validators as u64 * u64::from(bps) / MAX_BPS).max(1) as usize
+            }),
+            client_type_counts: HashMap::new(),
+            subnet_counts: HashMap::new(),
+        }
+    }
+
+    /// The constraint selecting the validator would break, if any. Validators without attributes
+    /// can't be checked, so they are kept out whenever a constraint depends on them.
+    pub fn binding_constraint(
+        &self,
+        attributes: Option<&ValidatorAttributes>,
+    ) -> Option<DiversityConstraint> {
+        let Some(attributes) = attributes else {
+            return self
+                .constraints
+                .uses_attributes()
+                .then_some(DiversityConstraint::UnknownAttributes);
+        };
+        if self.constraints.exclude_superminority && attributes.is_superminority {
+            return Some(DiversityConstraint::Superminority);
+        }
+        if let Some(max) = self.max_validators_per_client_type {
+            let count = self
+                .client_type_counts
+                .get(&attributes.client_type)
+                .copied()
+                .unwrap_or(0);
+            if count >= max {
+                return Some(DiversityConstraint::ClientType);
+            }
+        }
+        if let Some(max) = self.constraints.max_validators_per_subnet {
+            let count = self
+                .subnet_counts
+                .get(&attributes.subnet)
+                .copied()
+                .unwrap_or(0);
+            if count >= max {
+                return Some(DiversityConstraint::Subnet);
+            }
+        }
+        None
+    }
+
+    pub fn add(&mut self, attributes: &ValidatorAttributes) {
+        *self
+            .client_type_counts
+            .entry(attributes.client_type)
+            .or_default() += 1;
+        *self.subnet_counts.entry(attributes.subnet).or_default() += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attributes(client_type: u8, subnet: [u8; 2]) -> ValidatorAttributes {
+        ValidatorAttributes {
+            client_type,
+            subnet,
+            is_superminority: false,
+        }
+    }
+
+    #[test]
+    fn test_subnet_limit_binds_once_reached() {
+        let constraints = DiversityConstraints {
+            max_validators_per_subnet: Some(1),
+            ..Default::default()
+        };
+        let mut selection = DiversitySelection::new(&constraints, 4);
+
+        selection.add(&attributes(0, [10, 0]));
+        assert_eq!(
+            selection.binding_constraint(Some(&attributes(1, [10, 0]))),
+            Some(DiversityConstraint::Subnet)
+        );
+        assert_eq!(
+            selection.binding_constraint(Some(&attributes(0, [10, 1]))),
+            None
+        );
+    }
+
+    #[test]
+    fn test_client_type_share_binds_once_reached() {
+        let constraints = DiversityConstraints {
+            max_client_type_share_bps: Some(5_000),
+            ..Default::default()
+        };
+        let mut selection = DiversitySelection::new(&constraints, 4);
+
+        selection.add(&attributes(0, [10, 0]));
+        assert_eq!(
+            selection.binding_constraint(Some(&attributes(0, [10, 1]))),
+            None
+        );
+        selection.add(&attributes(0, [10, 1]));
+        assert_eq!(
+            selection.binding_constraint(Some(&attributes(0, [10, 2]))),
+            Some(DiversityConstraint::ClientType)
+        );
+        assert_eq!(
+            selection.binding_constraint(Some(&attributes(1, [10, 2]))),
+            None
+        );
+    }
+
+    #[test]
+    fn test_missing_attributes_bind_only_when_constrained() {
+        let unconstrained = DiversityConstraints {
+            max_validator_stake_bps: Some(500),
+            ..Default::default()
+        };
+        assert_eq!(
+            DiversitySelection::new(&unconstrained, 4).binding_constraint(None),
+            None
+        );
+
+        let constraints = DiversityConstraints {
+            exclude_superminority: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            DiversitySelection::new(&constraints, 4).binding_constraint(None),
+            Some(DiversityConstraint::UnknownAttributes)
+        );
+    }
+
+    #[test]
+    fn test_validator_stake_cap_is_optional() {
+        assert_eq!(
+            DiversityConstraints::default().validator_stake_cap(1_000_000),
+            None
+        );
+        let constraints = DiversityConstraints {
+            max_validator_stake_bps: Some(500),
+            ..Default::default()
+        };
+        assert_eq!(constraints.validator_stake_cap(1_000_000), Some(50_000));
+    }
+}
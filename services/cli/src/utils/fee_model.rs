@@ -0,0 +1,156 @@
+use spl_stake_pool::state::{Fee, StakePool};
+
+const MAX_BPS: u64 = 10_000;
+
+/// Fees charged by the stake pool, expressed in basis points (referral fees are a percentage of
+/// the deposit fee, as in the stake pool program).
+#[derive(Clone, Debug, Default)]
+pub struct StakePoolFeeModel {
+    pub epoch_fee_bps: u16,
+    pub stake_deposit_fee_bps: u16,
+    pub stake_withdrawal_fee_bps: u16,
+    pub sol_deposit_fee_bps: u16,
+    pub sol_withdrawal_fee_bps: u16,
+    pub stake_referral_fee_pct: u8,
+    pub sol_referral_fee_pct: u8,
+}
+
+/// Lamports deposited into and withdrawn from the pool in an epoch. Stake flows are delegated
+/// to or taken from validators, SOL flows go through the reserve.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PoolFlows {
+    pub stake_deposits: u64,
+    pub stake_withdrawals: u64,
+    pub sol_deposits: u64,
+    pub sol_withdrawals: u64,
+}
+
+/// Fees collected by the pool in a single simulated epoch, in lamports
+#[derive(Clone, Debug, Default)]
+pub struct EpochFeeRevenue {
+    pub epoch: u16,
+    pub gross_rewards: u64,
+    pub epoch_fee: u64,
+    pub deposit_fee: u64,
+    pub withdrawal_fee: u64,
+    /// Portion of the deposit fee paid out to referrers instead of the pool manager
+    pub referral_fee: u64,
+}
+
+impl EpochFeeRevenue {
+    /// Fee revenue kept by the pool manager
+    pub fn manager_revenue(&self) -> u64 {
+        self.epoch_fee + self.deposit_fee + self.withdrawal_fee - self.referral_fee
+    }
+}
+
+impl StakePoolFeeModel {
+    pub fn from_stake_pool(stake_pool: &StakePool) -> Self {
+        Self {
+            epoch_fee_bps: fee_to_bps(&stake_pool.epoch_fee),
+            stake_deposit_fee_bps: fee_to_bps(&stake_pool.stake_deposit_fee),
+            stake_withdrawal_fee_bps: fee_to_bps(&stake_pool.stake_withdrawal_fee),
+            sol_deposit_fee_bps: fee_to_bps(&stake_pool.sol_deposit_fee),
+            sol_withdrawal_fee_bps: fee_to_bps(&stake_pool.sol_withdrawal_fee),
+            stake_referral_fee_pct: stake_pool.stake_referral_fee,
+            sol_referral_fee_pct: stake_pool.sol_referral_fee,
+        }
+    }
+
+    pub fn epoch_fee(&self, rewards: u64) -> u64 {
+        apply_bps(rewards, self.epoch_fee_bps)
+    }
+
+    /// Returns the total fee on a stake deposit and the part of it that goes to the referrer
+    pub fn stake_deposit_fee(&self, amount: u64) -> (u64, u64) {
+        let fee = apply_bps(amount, self.stake_deposit_fee_bps);
+        let referral_fee = fee * u64::from(self.stake_referral_fee_pct.min(100)) / 100;
+        (fee, referral_fee)
+    }
+
+    pub fn stake_withdrawal_fee(&self, amount: u64) -> u64 {
+        apply_bps(amount, self.stake_withdrawal_fee_bps)
+    }
+
+    /// Returns the total fee on a SOL deposit and the part of it that goes to the referrer
+    pub fn sol_deposit_fee(&self, amount: u64) -> (u64, u64) {
+        let fee = apply_bps(amount, self.sol_deposit_fee_bps);
+        let referral_fee = fee * u64::from(self.sol_referral_fee_pct.min(100)) / 100;
+        (fee, referral_fee)
+    }
+
+    pub fn sol_withdrawal_fee(&self, amount: u64) -> u64 {
+        apply_bps(amount, self.sol_withdrawal_fee_bps)
+    }
+
+    /// Returns the deposit, withdrawal and referral fees charged on the flows
+    pub fn flow_fees(&self, flows: &PoolFlows) -> (u64, u64, u64) {
+        let (stake_deposit_fee, stake_referral_fee) = self.stake_deposit_fee(flows.stake_deposits);
+        let (sol_deposit_fee, sol_referral_fee) = self.sol_deposit_fee(flows.sol_deposits);
+        let withdrawal_fee = self.stake_withdrawal_fee(flows.stake_withdrawals)
+            + self.sol_withdrawal_fee(flows.sol_withdrawals);
+        (
+            stake_deposit_fee + sol_deposit_fee,
+            withdrawal_fee,
+            stake_referral_fee + sol_referral_fee,
+        )
+    }
+
+    /// Pool tokens minted to the manager for the epoch fee, mirroring
+    /// `StakePool::calc_epoch_fee_amount` where `total_lamports` already includes the rewards.
+    pub fn epoch_fee_pool_tokens(
+        &self,
+        rewards: u64,
+        total_lamports: u64,
+        pool_token_supply: u64,
+    ) -> u64 {
+        let fee_lamports = self.epoch_fee(rewards);
+        if fee_lamports == 0 || total_lamports <= fee_lamports {
+            return fee_lamports;
+        }
+        (u128::from(pool_token_supply) * u128::from(fee_lamports)
+            / u128::from(total_lamports - fee_lamports))
+        .min(u64::MAX as u128) as u64
+    }
+}
+
+fn fee_to_bps(fee: &Fee) -> u16 {
+    if fee.denominator == 0 {
+        return 0;
+    }
+    (u128::from(fee.numerator) * u128::from(MAX_BPS) / u128::from(fee.denominator))
+        .min(u128::from(MAX_BPS)) as u16
+}
+
+fn apply_bps(amount: u64, bps: u16) -> u64 {
+    (u128::from(amount) * u128::from(bps.min(MAX_BPS as u16)) / u128::from(MAX_BPS)) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flow_fees_charge_each_side_of_the_pool() {
+        let fee_model = StakePoolFeeModel {
+            stake_deposit_fee_bps: 10,
+            stake_withdrawal_fee_bps: 30,
+            sol_deposit_fee_bps: 20,
+            sol_withdrawal_fee_bps: 40,
+            stake_referral_fee_pct: 50,
+            sol_referral_fee_pct: 100,
+            ..Default::default()
+        };
+        let flows = PoolFlows {
+            stake_deposits: 1_000_000,
+            stake_withdrawals: 1_000_000,
+            sol_deposits: 2_000_000,
+            sol_withdrawals: 500_000,
+        };
+
+        let (deposit_fee, withdrawal_fee, referral_fee) = fee_model.flow_fees(&flows);
+        assert_eq!(deposit_fee, 1_000 + 4_000);
+        assert_eq!(withdrawal_fee, 3_000 + 2_000);
+        assert_eq!(referral_fee, 500 + 4_000);
+    }
+}
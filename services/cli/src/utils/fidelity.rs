@@ -0,0 +1,103 @@
+use std::collections::{HashMap, HashSet};
+
+/// How closely a simulated steward cycle matches what the JitoSOL pool actually delegated
+#[derive(Clone, Debug)]
+pub struct CycleFidelity {
+    pub start_epoch: u16,
+    pub end_epoch: u16,
+    /// Share of the simulated validator set the pool actually delegated to
+    pub precision: f64,
+    /// Share of the validators the pool actually delegated to that the simulation selected
+    pub recall: f64,
+    /// Sum over all validators of the smaller of their simulated and actual share of the stake.
+    /// 1.0 means the simulated allocation matches the actual one exactly.
+    pub stake_weighted_overlap: f64,
+}
+
+impl CycleFidelity {
+    /// Compares the simulated validator targets with the pool's actual stake per validator.
+    /// Validators with no more than `min_delegation_lamports` of actual stake only hold the
+    /// pool's minimum delegation and don't count as selected.
+    pub fn new(
+        start_epoch: u16,
+        end_epoch: u16,
+        simulated_targets: &HashMap<String, u64>,
+        actual_stakes: &HashMap<String, u64>,
+        min_delegation_lamports: u64,
+    ) -> Self {
+        let simulated: HashMap<&String, u64> = simulated_targets
+            .iter()
+            .filter(|(_, target)| **target > 0)
+            .map(|(vote_account, target)| (vote_account, *target))
+            .collect();
+        let actual: HashMap<&String, u64> = actual_stakes
+            .iter()
+            .filter(|(_, stake)| **stake > min_delegation_lamports)
+            .map(|(vote_account, stake)| (vote_account, *stake))
+            .collect();
+
+        let simulated_set: HashSet<&String> = simulated.keys().copied().collect();
+        let actual_set: HashSet<&String> = actual.keys().copied().collect();
+        let intersection = simulated_set.intersection(&actual_set).count() as f64;
+
+        let precision = if simulated_set.is_empty() {
+            0.0
+        } else {
+            intersection / simulated_set.len() as f64
+        };
+        let recall = if actual_set.is_empty() {
+            0.0
+        } else {
+            intersection / actual_set.len() as f64
+        };
+
+        let simulated_total: u64 = simulated.values().sum();
+        let actual_total: u64 = actual.values().sum();
+        let stake_weighted_overlap = if simulated_total == 0 || actual_total == 0 {
+            0.0
+        } else {
+            simulated_set
+                .intersection(&actual_set)
+                .map(|vote_account| {
+                    let simulated_share = simulated[vote_account] as f64 / simulated_total as f64;
+                    let actual_share = actual[vote_account] as f64 / actual_total as f64;
+                    simulated_share.min(actual_share)
+                })
+                .sum()
+        };
+
+        Self {
+            start_epoch,
+            end_epoch,
+            precision,
+            recall,
+            stake_weighted_overlap,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle_fidelity() {
+        let simulated = HashMap::from([
+            ("a".to_string(), 100),
+            ("b".to_string(), 100),
+            ("c".to_string(), 0),
+        ]);
+        let actual = HashMap::from([
+            ("a".to_string(), 300),
+            ("c".to_string(), 100),
+            ("d".to_string(), 5),
+        ]);
+
+        let fidelity = CycleFidelity::new(0, 10, &simulated, &actual, 10);
+        // `c` has no simulated target and `d` only holds the minimum delegation
+        assert_eq!(fidelity.precision, 0.5);
+        assert_eq!(fidelity.recall, 0.5);
+        // `a` is 50% of the simulated stake and 75% of the actual stake
+        assert_eq!(fidelity.stake_weighted_overlap, 0.5);
+    }
+}
@@ -0,0 +1,161 @@
+use clap::ValueEnum;
+use stakenet_simulator_db::epoch_rewards::EpochRewards;
+
+/// Slots in a year at the 400ms target slot time, as used by the runtime's inflation
+const SLOTS_PER_YEAR: f64 = 78_892_314.984;
+/// Epoch mainnet-beta enabled full inflation, the start of the disinflation schedule
+const FULL_INFLATION_EPOCH: u16 = 150;
+/// Parameters of the issuance schedule the recorded rewards were paid under
+const HISTORICAL_INITIAL_RATE: f64 = 0.08;
+const HISTORICAL_DISINFLATION_RATE: f64 = 0.15;
+const HISTORICAL_TERMINAL_RATE: f64 = 0.015;
+/// Weight of the term raising issuance below a 50% staking rate, as proposed in SIMD-0228
+const STAKING_RATE_SENSITIVITY: f64 = 3.0;
+
+/// Issuance curve the inflation rewards are recomputed under
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum InflationCurve {
+    /// The recorded rewards, paid under the cluster's actual issuance
+    #[default]
+    Historical,
+    /// The initial rate decreasing by the disinflation rate every year since full inflation was
+    /// enabled, down to the terminal rate
+    Disinflation,
+    /// The disinflation curve scaled by the staking rate `s` by `1 - sqrt(s) + 3 max(1 -
+    /// sqrt(2s), 0)`, so issuance drops as more of the supply is staked
+    StakingRateDependent,
+    /// A fixed annual rate
+    Constant,
+}
+
+/// Alternative issuance schedule the inflation rewards are rescaled to. MEV and priority fee
+/// rewards are kept as recorded.
+#[derive(Clone, Debug)]
+pub struct InflationSchedule {
+    pub curve: InflationCurve,
+    pub initial_rate: f64,
+    pub disinflation_rate: f64,
+    pub terminal_rate: f64,
+    /// Annual rate of the constant curve
+    pub constant_rate: Option<f64>,
+}
+
+impl Default for InflationSchedule {
+    fn default() -> Self {
+        Self {
+            curve: InflationCurve::Historical,
+            initial_rate: HISTORICAL_INITIAL_RATE,
+            disinflation_rate: HISTORICAL_DISINFLATION_RATE,
+            terminal_rate: HISTORICAL_TERMINAL_RATE,
+            constant_rate: None,
+        }
+    }
+}
+
+/// Annual rate of a disinflation curve `years` after full inflation was enabled
+fn disinflation_curve_rate(
+    initial_rate: f64,
+    disinflation_rate: f64,
+    terminal_rate: f64,
+    years: f64,
+) -> f64 {
+    (initial_rate * (1.0 - disinflation_rate).powf(years)).max(terminal_rate)
+}
+
+impl InflationSchedule {
+    /// Annual issuance rate at `epoch` under the schedule, given the share of the supply staked
+    pub fn rate_at(&self, epoch: u16, slots_per_epoch: u64, staking_rate: f64) -> f64 {
+        let years = f64::from(epoch.saturating_sub(FULL_INFLATION_EPOCH)) * slots_per_epoch as f64
+            / SLOTS_PER_YEAR;
+        let disinflation_rate = disinflation_curve_rate(
+            self.initial_rate,
+            self.disinflation_rate,
+            self.terminal_rate,
+            years,
+        );
+
+        match self.curve {
+            InflationCurve::Historical => disinflation_curve_rate(
+                HISTORICAL_INITIAL_RATE,
+                HISTORICAL_DISINFLATION_RATE,
+                HISTORICAL_TERMINAL_RATE,
+                years,
+            ),
+            InflationCurve::Disinflation => disinflation_rate,
+            InflationCurve::StakingRateDependent => {
+                let staking_rate = staking_rate.clamp(0.0, 1.0);
+                let multiplier = 1.0 - staking_rate.sqrt()
+                    + STAKING_RATE_SENSITIVITY * (1.0 - (2.0 * staking_rate).sqrt()).max(0.0);
+                disinflation_rate * multiplier
+            }
+            InflationCurve::Constant => self.constant_rate.unwrap_or(disinflation_rate),
+        }
+    }
+
+    /// Rescales the epoch's inflation rewards from the historical issuance to the schedule's.
+    /// The staking rate is estimated from the rewards: the supply is what the historical rate
+    /// issued over the epoch, over the epoch's share of a year.
+    pub fn apply_to_rewards(&self, epoch: u16, slots_per_epoch: u64, rewards: &mut [EpochRewards]) {
+        if self.curve == InflationCurve::Historical {
+            return;
+        }
+
+        let historical_rate = Self::default().rate_at(epoch, slots_per_epoch, 0.0);
+        let epoch_issuance: u64 = rewards
+            .iter()
+            .map(|reward| reward.total_inflation_rewards)
+            .sum();
+        let total_stake: u64 = rewards.iter().map(|reward| reward.active_stake).sum();
+        let supply =
+            epoch_issuance as f64 / (historical_rate * slots_per_epoch as f64 / SLOTS_PER_YEAR);
+        let staking_rate = if supply > 0.0 {
+            total_stake as f64 / supply
+        } else {
+            0.0
+        };
+
+        let scale = self.rate_at(epoch, slots_per_epoch, staking_rate) / historical_rate;
+        for reward in rewards.iter_mut() {
+            reward.total_inflation_rewards = (reward.total_inflation_rewards as f64 * scale) as u64;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::epoch_schedule::EpochSchedule;
+    use stakenet_simulator_db::epoch_rewards::test_epoch_rewards;
+
+    fn reward(total_inflation_rewards: u64, total_mev_rewards: u64) -> EpochRewards {
+        EpochRewards {
+            inflation_commission_bps: 500,
+            total_inflation_rewards,
+            mev_commission_bps: 800,
+            total_mev_rewards,
+            priority_fee_commission_bps: 10_000,
+            ..test_epoch_rewards("", 800, 1_000_000_000)
+        }
+    }
+
+    #[test]
+    fn test_constant_curve_rescales_inflation_only() {
+        let slots_per_epoch = EpochSchedule::default().slots_per_epoch;
+        let historical_rate = InflationSchedule::default().rate_at(800, slots_per_epoch, 0.0);
+        let schedule = InflationSchedule {
+            curve: InflationCurve::Constant,
+            constant_rate: Some(historical_rate / 2.0),
+            ..Default::default()
+        };
+
+        let mut rewards = vec![reward(1_000_000, 50_000), reward(2_000_000, 0)];
+        schedule.apply_to_rewards(800, slots_per_epoch, &mut rewards);
+        assert_eq!(rewards[0].total_inflation_rewards, 500_000);
+        assert_eq!(rewards[0].total_mev_rewards, 50_000);
+        assert_eq!(rewards[1].total_inflation_rewards, 1_000_000);
+
+        let mut unchanged = vec![reward(1_000_000, 50_000)];
+        InflationSchedule::default().apply_to_rewards(800, slots_per_epoch, &mut unchanged);
+        assert_eq!(unchanged[0].total_inflation_rewards, 1_000_000);
+    }
+}
@@ -0,0 +1,120 @@
+use crate::error::CliError;
+
+/// Why lamports moved in the simulated pool
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LamportMovementKind {
+    /// Initial lamports the pool starts with
+    Seed,
+    /// Stake deposited into the pool
+    Deposit,
+    /// Stake withdrawn from the pool
+    Withdrawal,
+    /// Staking rewards earned by a validator
+    Reward,
+    /// Lamports created or lost when a stake flow split across validators is rounded to whole
+    /// lamports, less than one per validator
+    Rounding,
+    /// Reverses the part of a deposit or withdrawal the simulated pool couldn't apply, such as
+    /// a withdrawal larger than the simulated stake
+    UnappliedFlow,
+    /// Stake delegated to a validator from deactivating stake or the reserve
+    Activation,
+    /// Stake deactivated on a validator
+    Deactivation,
+    /// Deactivated stake that wasn't delegated again returning to the reserve
+    ReserveReturn,
+}
+
+impl LamportMovementKind {
+    /// Whether lamports of this kind enter or leave the pool, as opposed to moving within it
+    pub fn changes_pool_balance(&self) -> bool {
+        matches!(
+            self,
+            Self::Seed
+                | Self::Deposit
+                | Self::Withdrawal
+                | Self::Reward
+                | Self::Rounding
+                | Self::UnappliedFlow
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LamportMovement {
+    pub epoch: u16,
+    pub kind: LamportMovementKind,
+    /// Validator the lamports moved on, `None` for movements of the pool as a whole
+    pub vote_account: Option<String>,
+    pub lamports: i128,
+}
+
+/// Record of every lamport movement in the simulated pool. The pool's lamports must always
+/// equal the sum of the movements that enter or leave the pool.
+#[derive(Clone, Debug, Default)]
+pub struct LamportLedger {
+    pub movements: Vec<LamportMovement>,
+    expected_pool_lamports: i128,
+}
+
+impl LamportLedger {
+    pub fn record(
+        &mut self,
+        epoch: u16,
+        kind: LamportMovementKind,
+        vote_account: Option<&str>,
+        lamports: i128,
+    ) {
+        if lamports == 0 {
+            return;
+        }
+        if kind.changes_pool_balance() {
+            self.expected_pool_lamports += lamports;
+        }
+        self.movements.push(LamportMovement {
+            epoch,
+            kind,
+            vote_account: vote_account.map(str::to_string),
+            lamports,
+        });
+    }
+
+    /// Lamports the pool should hold given the recorded movements
+    pub fn expected_pool_lamports(&self) -> i128 {
+        self.expected_pool_lamports
+    }
+
+    /// Errors if the pool's actual lamports differ from the recorded movements
+    pub fn check_conservation(&self, epoch: u16, pool_lamports: u64) -> Result<(), CliError> {
+        if i128::from(pool_lamports) != self.expected_pool_lamports {
+            return Err(CliError::LamportConservationViolation {
+                epoch,
+                expected: self.expected_pool_lamports,
+                actual: pool_lamports,
+            });
+        }
+        Ok(())
+    }
+
+    /// Errors if more than `max_lamports` were rounded away or created in `epoch`
+    pub fn check_rounding(&self, epoch: u16, max_lamports: u64) -> Result<(), CliError> {
+        let rounding = self.epoch_total(epoch, LamportMovementKind::Rounding);
+        if rounding.unsigned_abs() > u128::from(max_lamports) {
+            return Err(CliError::RoundingOutOfBounds {
+                epoch,
+                rounding,
+                max: max_lamports,
+            });
+        }
+        Ok(())
+    }
+
+    /// Net lamports of `kind` moved in `epoch`
+    pub fn epoch_total(&self, epoch: u16, kind: LamportMovementKind) -> i128 {
+        self.movements
+            .iter()
+            .filter(|movement| movement.epoch == epoch && movement.kind == kind)
+            .map(|movement| movement.lamports)
+            .sum()
+    }
+}
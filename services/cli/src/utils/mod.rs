@@ -1,8 +1,53 @@
 pub mod rebalancing_simulator;
 pub use rebalancing_simulator::*;
 
+pub mod diversity_constraints;
+pub use diversity_constraints::*;
+
+pub mod fee_model;
+pub use fee_model::*;
+
+pub mod decentralization;
+pub use decentralization::*;
+
+pub mod fidelity;
+pub use fidelity::*;
+
+pub mod priority_fee_model;
+pub use priority_fee_model::*;
+
+pub mod projection;
+pub use projection::*;
+
+pub mod rug_exposure;
+pub use rug_exposure::*;
+
+pub mod scenario;
+pub use scenario::*;
+
+pub mod skip_rate;
+pub use skip_rate::*;
+
+pub mod score_diff;
+pub use score_diff::*;
+
+pub mod allocation;
+pub use allocation::*;
+
 pub mod backtest_utilization;
 pub use backtest_utilization::*;
 
+pub mod inflation_schedule;
+pub use inflation_schedule::*;
+
+pub mod lamport_ledger;
+pub use lamport_ledger::*;
+
+pub mod turnover;
+pub use turnover::*;
+
 pub mod validator_stake_state;
 pub use validator_stake_state::*;
+
+pub mod validator_overrides;
+pub use validator_overrides::*;
@@ -0,0 +1,182 @@
+use clap::ValueEnum;
+use stakenet_simulator_db::epoch_rewards::EpochRewards;
+use std::collections::HashMap;
+use validator_history::ValidatorHistoryEntry as JitoValidatorHistoryEntry;
+
+/// Where the priority fee rewards shared with stakers come from
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum PriorityFeeRewardModel {
+    /// The priority fee rewards and commission imported into `epoch_rewards`
+    #[default]
+    Recorded,
+    /// The priority fees and commission recorded in the validator history. Stakers receive the
+    /// recorded tips when there are any. Validators without recorded fees are estimated to earn
+    /// the cluster's average fees for every block they produced, or for every leader slot they
+    /// had when their block production isn't recorded.
+    BlockProduction,
+}
+
+/// Priority fees a validator earned in an epoch and the commission it kept of them
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PriorityFeeEstimate {
+    pub total_priority_fees: u64,
+    pub commission_bps: Option<u16>,
+}
+
+/// Cluster-wide priority fees of an epoch, used to estimate the fees of validators without
+/// recorded ones
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AveragePriorityFees {
+    pub per_block: f64,
+    /// Fees over every slot of the epoch, so the expected fees of a leader slot at the cluster's
+    /// skip rate
+    pub per_slot: f64,
+}
+
+/// Estimates the validator's priority fees from its history entry, `None` when it has neither
+/// fees, produced blocks nor leader slots recorded. Recorded tips are the stakers' share, so
+/// they are returned without commission.
+pub fn estimate_priority_fees(
+    entry: &JitoValidatorHistoryEntry,
+    average_fees: AveragePriorityFees,
+) -> Option<PriorityFeeEstimate> {
+    let unset = JitoValidatorHistoryEntry::default();
+    if entry.priority_fee_tips != unset.priority_fee_tips {
+        return Some(PriorityFeeEstimate {
+            total_priority_fees: entry.priority_fee_tips,
+            commission_bps: Some(0),
+        });
+    }
+
+    let total_priority_fees = if entry.total_priority_fees != unset.total_priority_fees {
+        entry.total_priority_fees
+    } else if entry.blocks_produced != unset.blocks_produced {
+        (f64::from(entry.blocks_produced) * average_fees.per_block) as u64
+    } else if entry.total_leader_slots != unset.total_leader_slots {
+        (f64::from(entry.total_leader_slots) * average_fees.per_slot) as u64
+    } else {
+        return None;
+    };
+
+    Some(PriorityFeeEstimate {
+        total_priority_fees,
+        commission_bps: (entry.priority_fee_commission != unset.priority_fee_commission)
+            .then_some(entry.priority_fee_commission.min(10_000)),
+    })
+}
+
+impl PriorityFeeRewardModel {
+    /// Replaces the priority fee rewards and commission of the epoch's rewards with the ones
+    /// derived from the validators' history entries. Rewards of validators the fees can't be
+    /// estimated for are left as recorded.
+    pub fn apply_to_rewards(
+        &self,
+        rewards: &mut [EpochRewards],
+        entries: &HashMap<String, JitoValidatorHistoryEntry>,
+        average_fees: AveragePriorityFees,
+    ) {
+        if *self == Self::Recorded {
+            return;
+        }
+
+        for reward in rewards.iter_mut() {
+            let Some(estimate) = entries
+                .get(&reward.vote_pubkey)
+                .and_then(|entry| estimate_priority_fees(entry, average_fees))
+            else {
+                continue;
+            };
+            reward.total_priority_fee_rewards = estimate.total_priority_fees;
+            if let Some(commission_bps) = estimate.commission_bps {
+                reward.priority_fee_commission_bps = commission_bps;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stakenet_simulator_db::epoch_rewards::test_epoch_rewards;
+
+    const AVERAGE_FEES: AveragePriorityFees = AveragePriorityFees {
+        per_block: 1_000.0,
+        per_slot: 900.0,
+    };
+
+    #[test]
+    fn test_estimate_falls_back_to_produced_blocks() {
+        let recorded = JitoValidatorHistoryEntry {
+            total_priority_fees: 5_000_000,
+            blocks_produced: 10,
+            priority_fee_commission: 5_000,
+            ..Default::default()
+        };
+        assert_eq!(
+            estimate_priority_fees(&recorded, AVERAGE_FEES),
+            Some(PriorityFeeEstimate {
+                total_priority_fees: 5_000_000,
+                commission_bps: Some(5_000),
+            })
+        );
+
+        let blocks_only = JitoValidatorHistoryEntry {
+            blocks_produced: 10,
+            total_leader_slots: 12,
+            ..Default::default()
+        };
+        assert_eq!(
+            estimate_priority_fees(&blocks_only, AVERAGE_FEES),
+            Some(PriorityFeeEstimate {
+                total_priority_fees: 10_000,
+                commission_bps: None,
+            })
+        );
+
+        let leader_slots_only = JitoValidatorHistoryEntry {
+            total_leader_slots: 12,
+            ..Default::default()
+        };
+        assert_eq!(
+            estimate_priority_fees(&leader_slots_only, AVERAGE_FEES),
+            Some(PriorityFeeEstimate {
+                total_priority_fees: 10_800,
+                commission_bps: None,
+            })
+        );
+
+        assert_eq!(
+            estimate_priority_fees(&JitoValidatorHistoryEntry::default(), AVERAGE_FEES),
+            None
+        );
+    }
+
+    #[test]
+    fn test_recorded_tips_are_the_staker_rewards() {
+        let tipped = JitoValidatorHistoryEntry {
+            priority_fee_tips: 2_500_000,
+            total_priority_fees: 5_000_000,
+            priority_fee_commission: 5_000,
+            ..Default::default()
+        };
+        let estimate = estimate_priority_fees(&tipped, AVERAGE_FEES).unwrap();
+        assert_eq!(
+            estimate,
+            PriorityFeeEstimate {
+                total_priority_fees: 2_500_000,
+                commission_bps: Some(0),
+            }
+        );
+
+        let mut rewards = [EpochRewards {
+            priority_fee_commission_bps: 10_000,
+            ..test_epoch_rewards("validator", 800, 1_000_000_000)
+        }];
+        PriorityFeeRewardModel::BlockProduction.apply_to_rewards(
+            &mut rewards,
+            &HashMap::from([("validator".to_string(), tipped)]),
+            AVERAGE_FEES,
+        );
+        assert_eq!(rewards[0].stake_after_epoch(500_000_000), 501_250_000);
+    }
+}
@@ -0,0 +1,501 @@
+use rand::{Rng, prelude::IndexedRandom};
+use stakenet_simulator_db::{
+    cluster_history_entry::ClusterHistoryEntry, epoch_rewards::EpochRewards,
+    validator_history_entry::ValidatorHistoryEntry,
+};
+use std::collections::HashMap;
+use validator_history::ValidatorHistoryEntry as JitoValidatorHistoryEntry;
+
+/// What a validator earned in an epoch it wasn't delinquent in, per lamport of active stake
+#[derive(Clone, Debug)]
+pub struct EpochPerformance {
+    pub epoch_credits: u32,
+    pub blocks_produced: u32,
+    pub total_leader_slots: u32,
+    pub inflation_per_stake: f64,
+    pub mev_per_stake: f64,
+    pub priority_fee_per_stake: f64,
+}
+
+/// Empirical distributions of a validator's behaviour over the fitting window
+#[derive(Clone, Debug)]
+pub struct ValidatorProfile {
+    pub vote_account: String,
+    /// Latest history entry, used as the template of the synthetic entries
+    pub last_entry: JitoValidatorHistoryEntry,
+    pub active_stake: u64,
+    pub priority_fee_commission_bps: u16,
+    /// Commissions the validator charged, one per epoch
+    pub commissions: Vec<u8>,
+    pub mev_commissions: Vec<u16>,
+    /// Probability the validator changes its commission or MEV commission in an epoch
+    pub commission_change_probability: f64,
+    pub delinquency_probability: f64,
+    pub performance: Vec<EpochPerformance>,
+}
+
+impl ValidatorProfile {
+    /// Fits a validator's profile from its history entries and rewards in the fitting window.
+    /// An epoch is delinquent when the validator earned less than `delinquency_threshold_ratio`
+    /// of the most epoch credits earned in it. Returns `None` without any rewards to fit.
+    pub fn fit(
+        vote_account: &str,
+        entries: &[&JitoValidatorHistoryEntry],
+        rewards: &HashMap<u16, &EpochRewards>,
+        max_epoch_credits: &HashMap<u16, u32>,
+        delinquency_threshold_ratio: f64,
+    ) -> Option<Self> {
+        let mut entries = entries.to_vec();
+        entries.sort_by_key(|entry| entry.epoch);
+        let last_entry = **entries.last()?;
+        let last_rewards = rewards.get(&last_entry.epoch).or_else(|| {
+            entries
+                .iter()
+                .rev()
+                .find_map(|entry| rewards.get(&entry.epoch))
+        })?;
+
+        let mut commission_changes = 0usize;
+        let mut delinquent_epochs = 0usize;
+        let mut performance = Vec::new();
+        for (index, entry) in entries.iter().enumerate() {
+            if index > 0
+                && (entry.commission != entries[index - 1].commission
+                    || entry.mev_commission != entries[index - 1].mev_commission)
+            {
+                commission_changes += 1;
+            }
+
+            let max_credits = max_epoch_credits.get(&entry.epoch).copied().unwrap_or(0);
+            if (entry.epoch_credits as f64) < max_credits as f64 * delinquency_threshold_ratio {
+                delinquent_epochs += 1;
+                continue;
+            }
+            let Some(reward) = rewards.get(&entry.epoch).filter(|r| r.active_stake > 0) else {
+                continue;
+            };
+            let per_stake = |lamports: u64| lamports as f64 / reward.active_stake as f64;
+            performance.push(EpochPerformance {
+                epoch_credits: entry.epoch_credits,
+                blocks_produced: entry.blocks_produced,
+                total_leader_slots: entry.total_leader_slots,
+                inflation_per_stake: per_stake(reward.total_inflation_rewards),
+                mev_per_stake: per_stake(reward.total_mev_rewards),
+                priority_fee_per_stake: per_stake(reward.total_priority_fee_rewards),
+            });
+        }
+
+        Some(Self {
+            vote_account: vote_account.to_string(),
+            last_entry,
+            active_stake: last_rewards.active_stake,
+            priority_fee_commission_bps: last_rewards.priority_fee_commission_bps,
+            commissions: entries.iter().map(|entry| entry.commission).collect(),
+            mev_commissions: entries.iter().map(|entry| entry.mev_commission).collect(),
+            commission_change_probability: commission_changes as f64
+                / (entries.len().saturating_sub(1).max(1)) as f64,
+            delinquency_probability: delinquent_epochs as f64 / entries.len() as f64,
+            performance,
+        })
+    }
+}
+
+/// History entries, rewards and cluster history generated for epochs past the recorded data
+#[derive(Default)]
+pub struct SyntheticEpochs {
+    pub first_epoch: u16,
+    pub entries: Vec<ValidatorHistoryEntry>,
+    pub rewards: HashMap<u16, Vec<EpochRewards>>,
+    pub cluster_history_entries: Vec<ClusterHistoryEntry>,
+}
+
+/// Validator profiles and cluster behaviour fitted on the recorded data, used to generate
+/// synthetic future epochs
+pub struct ProjectionModel {
+    pub profiles: Vec<ValidatorProfile>,
+    pub last_cluster_entry: ClusterHistoryEntry,
+    pub epoch_duration_seconds: u64,
+}
+
+impl ProjectionModel {
+    /// Fits the model on the entries, rewards and cluster history of the fitting window.
+    /// Returns `None` without cluster history to extend, or when the first or last epoch's start
+    /// timestamp isn't recorded or goes backwards.
+    pub fn fit(
+        entries: &[ValidatorHistoryEntry],
+        rewards: &[EpochRewards],
+        cluster_history_entries: &[ClusterHistoryEntry],
+        delinquency_threshold_ratio: f64,
+    ) -> Option<Self> {
+        let mut max_epoch_credits: HashMap<u16, u32> = HashMap::new();
+        let mut entries_by_validator: HashMap<&str, Vec<&JitoValidatorHistoryEntry>> =
+            HashMap::new();
+        for entry in entries {
+            let credits = max_epoch_credits
+                .entry(entry.validator_history_entry.epoch)
+                .or_default();
+            *credits = (*credits).max(entry.validator_history_entry.epoch_credits);
+            entries_by_validator
+                .entry(entry.vote_pubkey.as_str())
+                .or_default()
+                .push(&entry.validator_history_entry);
+        }
+
+        let mut rewards_by_validator: HashMap<&str, HashMap<u16, &EpochRewards>> = HashMap::new();
+        for reward in rewards {
+            rewards_by_validator
+                .entry(reward.vote_pubkey.as_str())
+                .or_default()
+                .insert(reward.epoch as u16, reward);
+        }
+
+        let profiles = entries_by_validator
+            .iter()
+            .filter_map(|(vote_account, entries)| {
+                ValidatorProfile::fit(
+                    vote_account,
+                    entries,
+                    rewards_by_validator.get(vote_account)?,
+                    &max_epoch_credits,
+                    delinquency_threshold_ratio,
+                )
+            })
+            .collect();
+
+        let mut cluster_history_entries = cluster_history_entries.to_vec();
+        cluster_history_entries.sort_by_key(|entry| entry.epoch);
+        let last_cluster_entry = cluster_history_entries.last()?.clone();
+        let first_cluster_entry = cluster_history_entries.first()?;
+        if first_cluster_entry.epoch_start_timestamp == 0 {
+            return None;
+        }
+        let epoch_duration_seconds = last_cluster_entry
+            .epoch_start_timestamp
+            .checked_sub(first_cluster_entry.epoch_start_timestamp)?
+            / u64::from((last_cluster_entry.epoch - first_cluster_entry.epoch).max(1));
+
+        Some(Self {
+            profiles,
+            last_cluster_entry,
+            epoch_duration_seconds,
+        })
+    }
+
+    /// Generates `epochs` synthetic epochs starting at `first_epoch`. Every validator keeps its
+    /// commission or switches to one it charged before, is delinquent with its fitted
+    /// probability and otherwise performs like in one of its recorded epochs.
+    pub fn generate(&self, first_epoch: u16, epochs: u16, rng: &mut impl Rng) -> SyntheticEpochs {
+        let mut synthetic = SyntheticEpochs {
+            first_epoch,
+            ..Default::default()
+        };
+
+        for offset in 0..epochs {
+            let epoch = first_epoch + offset;
+            synthetic.cluster_history_entries.push(ClusterHistoryEntry {
+                epoch,
+                total_blocks: self.last_cluster_entry.total_blocks,
+                epoch_start_timestamp: self.last_cluster_entry.epoch_start_timestamp
+                    + u64::from(epoch - self.last_cluster_entry.epoch)
+                        * self.epoch_duration_seconds,
+            });
+        }
+
+        for profile in &self.profiles {
+            let mut commission = profile.last_entry.commission;
+            let mut mev_commission = profile.last_entry.mev_commission;
+
+            for offset in 0..epochs {
+                let epoch = first_epoch + offset;
+                if rng.random_bool(profile.commission_change_probability.clamp(0.0, 1.0)) {
+                    commission = *profile.commissions.choose(rng).unwrap_or(&commission);
+                    mev_commission = *profile
+                        .mev_commissions
+                        .choose(rng)
+                        .unwrap_or(&mev_commission);
+                }
+
+                let mut entry = profile.last_entry;
+                entry.epoch = epoch;
+                entry.commission = commission;
+                entry.mev_commission = mev_commission;
+                entry.activated_stake_lamports = profile.active_stake;
+
+                let delinquent = rng.random_bool(profile.delinquency_probability.clamp(0.0, 1.0));
+                let performance = profile.performance.choose(rng).filter(|_| !delinquent);
+                let (inflation, mev, priority_fees) = match performance {
+                    Some(performance) => {
+                        entry.epoch_credits = performance.epoch_credits;
+                        entry.blocks_produced = performance.blocks_produced;
+                        entry.total_leader_slots = performance.total_leader_slots;
+                        let rewards =
+                            |per_stake: f64| (per_stake * profile.active_stake as f64) as u64;
+                        (
+                            rewards(performance.inflation_per_stake),
+                            rewards(performance.mev_per_stake),
+                            rewards(performance.priority_fee_per_stake),
+                        )
+                    }
+                    None => {
+                        entry.epoch_credits = 0;
+                        entry.blocks_produced = 0;
+                        entry.mev_earned = 0;
+                        (0, 0, 0)
+                    }
+                };
+
+                synthetic.entries.push(ValidatorHistoryEntry::new(
+                    profile.vote_account.clone(),
+                    entry,
+                ));
+                synthetic
+                    .rewards
+                    .entry(epoch)
+                    .or_default()
+                    .push(EpochRewards {
+                        id: format!("{}-{}", epoch, profile.vote_account),
+                        vote_pubkey: profile.vote_account.clone(),
+                        epoch: u64::from(epoch),
+                        inflation_commission_bps: u16::from(commission.min(100)) * 100,
+                        total_inflation_rewards: inflation,
+                        // Validators without a MEV commission keep all of the MEV
+                        mev_commission_bps: mev_commission.min(10_000),
+                        total_mev_rewards: mev,
+                        priority_fee_commission_bps: profile.priority_fee_commission_bps,
+                        total_priority_fee_rewards: priority_fees,
+                        active_stake: profile.active_stake,
+                    });
+            }
+        }
+
+        synthetic
+    }
+}
+
+/// Percentiles of the APYs of the projection runs
+#[derive(Clone, Debug, Default)]
+pub struct ApyDistribution {
+    samples: Vec<f64>,
+}
+
+impl ApyDistribution {
+    pub fn new(mut samples: Vec<f64>) -> Self {
+        samples.sort_by(|a, b| a.total_cmp(b));
+        Self { samples }
+    }
+
+    pub fn mean(&self) -> f64 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+        self.samples.iter().sum::<f64>() / self.samples.len() as f64
+    }
+
+    /// Nearest-rank percentile, `percentile` between 0 and 100
+    pub fn percentile(&self, percentile: f64) -> f64 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+        let rank = (percentile / 100.0 * self.samples.len() as f64).ceil() as usize;
+        self.samples[rank.clamp(1, self.samples.len()) - 1]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, rngs::StdRng};
+    use stakenet_simulator_db::epoch_rewards::test_epoch_rewards;
+
+    fn entry(epoch: u16, commission: u8, epoch_credits: u32) -> JitoValidatorHistoryEntry {
+        JitoValidatorHistoryEntry {
+            epoch,
+            commission,
+            mev_commission: 800,
+            epoch_credits,
+            blocks_produced: 90,
+            total_leader_slots: 100,
+            ..Default::default()
+        }
+    }
+
+    fn reward(epoch: u16, total_inflation_rewards: u64, active_stake: u64) -> EpochRewards {
+        EpochRewards {
+            inflation_commission_bps: 500,
+            total_inflation_rewards,
+            mev_commission_bps: 800,
+            priority_fee_commission_bps: 5_000,
+            ..test_epoch_rewards("", u64::from(epoch), active_stake)
+        }
+    }
+
+    fn profile(delinquency_probability: f64) -> ValidatorProfile {
+        ValidatorProfile {
+            vote_account: "validator".to_string(),
+            last_entry: entry(9, 5, 1_000),
+            active_stake: 4_000,
+            priority_fee_commission_bps: 5_000,
+            commissions: vec![5, 100],
+            mev_commissions: vec![800, 10_000],
+            commission_change_probability: 0.0,
+            delinquency_probability,
+            performance: vec![EpochPerformance {
+                epoch_credits: 900,
+                blocks_produced: 45,
+                total_leader_slots: 50,
+                inflation_per_stake: 0.25,
+                mev_per_stake: 0.5,
+                priority_fee_per_stake: 0.125,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_validator_profile_fit() {
+        let entries = [
+            entry(13, 7, 1_000),
+            entry(10, 5, 1_000),
+            entry(12, 7, 1_000),
+            entry(11, 5, 100),
+        ];
+        let entries: Vec<&JitoValidatorHistoryEntry> = entries.iter().collect();
+        let rewards = [
+            reward(10, 10, 1_000),
+            reward(11, 1, 1_000),
+            reward(13, 50, 2_000),
+        ];
+        let rewards: HashMap<u16, &EpochRewards> = rewards
+            .iter()
+            .map(|reward| (reward.epoch as u16, reward))
+            .collect();
+        let max_epoch_credits: HashMap<u16, u32> = (10..=13).map(|epoch| (epoch, 1_000)).collect();
+
+        let profile =
+            ValidatorProfile::fit("validator", &entries, &rewards, &max_epoch_credits, 0.9)
+                .unwrap();
+        assert_eq!(profile.last_entry.epoch, 13);
+        assert_eq!(profile.active_stake, 2_000);
+        assert_eq!(profile.priority_fee_commission_bps, 5_000);
+        assert_eq!(profile.commissions, vec![5, 5, 7, 7]);
+        // One change over three epoch transitions
+        assert!((profile.commission_change_probability - 1.0 / 3.0).abs() < 1e-12);
+        // Epoch 11 is delinquent, epoch 12 has no rewards to fit
+        assert!((profile.delinquency_probability - 0.25).abs() < 1e-12);
+        assert_eq!(profile.performance.len(), 2);
+        assert!((profile.performance[0].inflation_per_stake - 0.01).abs() < 1e-12);
+        assert!((profile.performance[1].inflation_per_stake - 0.025).abs() < 1e-12);
+
+        assert!(
+            ValidatorProfile::fit(
+                "validator",
+                &entries,
+                &HashMap::new(),
+                &max_epoch_credits,
+                0.9
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn test_projection_model_generate() {
+        let model = ProjectionModel {
+            profiles: vec![profile(0.0)],
+            last_cluster_entry: ClusterHistoryEntry {
+                epoch: 9,
+                total_blocks: 400_000,
+                epoch_start_timestamp: 1_000,
+            },
+            epoch_duration_seconds: 100,
+        };
+        let synthetic = model.generate(10, 3, &mut StdRng::seed_from_u64(0));
+
+        assert_eq!(synthetic.first_epoch, 10);
+        let timestamps: Vec<u64> = synthetic
+            .cluster_history_entries
+            .iter()
+            .map(|entry| entry.epoch_start_timestamp)
+            .collect();
+        assert_eq!(timestamps, vec![1_100, 1_200, 1_300]);
+        assert_eq!(synthetic.entries.len(), 3);
+        for (offset, entry) in synthetic.entries.iter().enumerate() {
+            let entry = &entry.validator_history_entry;
+            assert_eq!(entry.epoch, 10 + offset as u16);
+            assert_eq!(entry.commission, 5);
+            assert_eq!(entry.epoch_credits, 900);
+            assert_eq!(entry.total_leader_slots, 50);
+            assert_eq!(entry.activated_stake_lamports, 4_000);
+        }
+        let rewards = &synthetic.rewards[&11][0];
+        assert_eq!(rewards.inflation_commission_bps, 500);
+        assert_eq!(rewards.total_inflation_rewards, 1_000);
+        assert_eq!(rewards.total_mev_rewards, 2_000);
+        assert_eq!(rewards.total_priority_fee_rewards, 500);
+
+        let delinquent = ProjectionModel {
+            profiles: vec![profile(1.0)],
+            ..model
+        };
+        let synthetic = delinquent.generate(10, 3, &mut StdRng::seed_from_u64(0));
+        assert!(
+            synthetic
+                .entries
+                .iter()
+                .all(|entry| entry.validator_history_entry.epoch_credits == 0)
+        );
+        assert!(synthetic.rewards.values().flatten().all(|reward| {
+            reward.total_inflation_rewards == 0
+                && reward.total_mev_rewards == 0
+                && reward.total_priority_fee_rewards == 0
+        }));
+    }
+
+    #[test]
+    fn test_projection_model_fit_requires_recorded_timestamps() {
+        let cluster_entry = |epoch: u16, epoch_start_timestamp: u64| ClusterHistoryEntry {
+            epoch,
+            total_blocks: 400_000,
+            epoch_start_timestamp,
+        };
+
+        let model = ProjectionModel::fit(
+            &[],
+            &[],
+            &[cluster_entry(12, 1_400), cluster_entry(10, 1_000)],
+            0.9,
+        )
+        .unwrap();
+        assert_eq!(model.last_cluster_entry.epoch, 12);
+        assert_eq!(model.epoch_duration_seconds, 200);
+
+        // An unrecorded timestamp at either end of the window is zero
+        assert!(
+            ProjectionModel::fit(
+                &[],
+                &[],
+                &[cluster_entry(10, 1_000), cluster_entry(12, 0)],
+                0.9
+            )
+            .is_none()
+        );
+        assert!(
+            ProjectionModel::fit(
+                &[],
+                &[],
+                &[cluster_entry(10, 0), cluster_entry(12, 1_400)],
+                0.9
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn test_apy_distribution_percentiles() {
+        let distribution = ApyDistribution::new((1..=20).rev().map(f64::from).collect());
+        assert_eq!(distribution.percentile(5.0), 1.0);
+        assert_eq!(distribution.percentile(50.0), 10.0);
+        assert_eq!(distribution.percentile(95.0), 19.0);
+        assert_eq!(distribution.percentile(100.0), 20.0);
+        assert!((distribution.mean() - 10.5).abs() < 1e-12);
+    }
+}
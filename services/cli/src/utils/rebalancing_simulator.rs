@@ -1,4 +1,15 @@
-use crate::{error::CliError, utils::ValidatorStakeState};
+use crate::{
+    error::CliError,
+    utils::{
+        AllocationMode, AveragePriorityFees, CycleTurnover, DiversityConstraints,
+        DiversitySelection, EpochDecentralization, EpochFeeRevenue, InflationSchedule,
+        LamportLedger, LamportMovementKind, LeaderSlots, PoolFlows, PriorityFeeRewardModel,
+        RugExposure, Scenario, SkipRateScoring, StakePoolFeeModel, SyntheticEpochs,
+        ValidatorAttributes, ValidatorOverride, ValidatorStakeSnapshot, ValidatorStakeState,
+        allocation_targets, is_commission_rug, skip_rate,
+    },
+};
+use clap::ValueEnum;
 use futures::future::try_join_all;
 use jito_steward::{
     Config,
@@ -8,38 +19,163 @@ use jito_steward::{
 use num_traits::ToPrimitive;
 use rand::prelude::IndexedRandom;
 use rand::rng;
-use solana_sdk::native_token::LAMPORTS_PER_SOL;
-use sqlx::{Pool, Postgres};
+use solana_sdk::{epoch_schedule::EpochSchedule, native_token::LAMPORTS_PER_SOL};
+use sqlx::{Pool, Postgres, types::BigDecimal};
 use stakenet_simulator_db::{
-    active_stake_jito_sol::ActiveStakeJitoSol, cluster_history::ClusterHistory,
-    cluster_history_entry::ClusterHistoryEntry, epoch_rewards::EpochRewards,
-    validator_history::ValidatorHistory, validator_history_entry::ValidatorHistoryEntry,
+    active_stake_jito_sol::ActiveStakeJitoSol,
+    cluster_history::ClusterHistory,
+    cluster_history_entry::ClusterHistoryEntry,
+    epoch_block_production::{EpochBlockProduction, VoteAccountBlockProduction},
+    epoch_priority_fees::EpochPriorityFees,
+    epoch_rewards::EpochRewards,
+    epoch_schedule::EpochSchedule as DbEpochSchedule,
+    jito_sol_validator_stakes::JitoSolValidatorStake,
+    steward_config_history::StewardConfigHistory,
+    validator_history::ValidatorHistory,
+    validator_history_entry::ValidatorHistoryEntry,
     withdraw_and_deposits::WithdrawsAndDeposits,
 };
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use tracing::{error, info};
-use validator_history::ClusterHistory as JitoClusterHistory;
+use tracing::{error, info, warn};
+use validator_history::{
+    ClusterHistory as JitoClusterHistory, ValidatorHistoryEntry as JitoValidatorHistoryEntry,
+};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct RebalancingCycle {
+    pub start_epoch: u16,
+    pub end_epoch: u16,
+    /// Target stake of every validator selected at the start of the cycle
+    pub validator_targets: HashMap<String, u64>,
     pub starting_total_lamports: u64,
     pub ending_total_lamports: u64,
+    /// Share of `ending_total_lamports` owned by pool token holders, i.e. excluding the pool
+    /// tokens minted to the manager and referrers as fees
+    pub ending_holder_lamports: u64,
+    /// Pool tokens owned by the holders. The pool is seeded at one token per lamport, so
+    /// `ending_holder_lamports / ending_holder_pool_tokens` is the growth of the exchange rate.
+    pub ending_holder_pool_tokens: u64,
+    pub epoch_fees: Vec<EpochFeeRevenue>,
+    pub turnover: CycleTurnover,
+    pub decentralization: Vec<EpochDecentralization>,
+    /// Commission rugs of delegated validators that happened during the cycle
+    pub rug_exposures: Vec<RugExposure>,
 }
 
 #[derive(Debug, Clone)]
 pub struct EpochWithdrawDepositStakeData {
-    pub withdraw_stake: f64,
-    pub deposit_stake: f64,
-    pub active_balance: f64,
+    pub vote_pubkey: String,
+    pub withdraw_lamports: u64,
+    pub deposit_lamports: u64,
+    /// The pool's actual active stake for the epoch
+    pub active_balance_lamports: u64,
+}
+
+impl EpochWithdrawDepositStakeData {
+    /// The deposit scaled to a pool holding `pool_lamports` instead of the actual active balance
+    pub fn scaled_deposit(&self, pool_lamports: u64) -> u64 {
+        self.scale(self.deposit_lamports, pool_lamports)
+    }
+
+    /// The withdrawal scaled to a pool holding `pool_lamports` instead of the actual active
+    /// balance
+    pub fn scaled_withdrawal(&self, pool_lamports: u64) -> u64 {
+        self.scale(self.withdraw_lamports, pool_lamports)
+    }
+
+    /// `lamports * pool_lamports / active_balance_lamports`, rounded down
+    fn scale(&self, lamports: u64, pool_lamports: u64) -> u64 {
+        if self.active_balance_lamports == 0 {
+            return 0;
+        }
+        (u128::from(lamports) * u128::from(pool_lamports)
+            / u128::from(self.active_balance_lamports))
+        .min(u128::from(u64::MAX)) as u64
+    }
+}
+
+/// How historical deposits and withdrawals are assigned to simulated validators
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum StakeFlowAttribution {
+    /// Each record is applied to a random validator in the simulated set
+    #[default]
+    Random,
+    /// Each record is applied to the validator it actually happened on, when that validator is
+    /// in the simulated set
+    Actual,
+}
+
+/// Where deposits and withdrawals that can't be attributed to a simulated validator go
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum UnattributedStakeFlowPolicy {
+    /// Spread across the simulated set proportionally to each validator's active stake
+    #[default]
+    ProRata,
+    /// Deposits sit in the pool reserve until the next steward cycle, withdrawals are taken from
+    /// the reserve first. Flows going through the reserve are charged the SOL deposit and
+    /// withdrawal fees.
+    Reserve,
+    /// Applied to a random validator in the simulated set
+    Random,
+}
+
+/// Which stake earns rewards in an epoch
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum RewardEligibility {
+    /// Only active stake earns. Deactivated stake stops earning immediately and deactivated
+    /// lamports start activating on other validators in the same epoch.
+    #[default]
+    ActiveOnly,
+    /// Stake effective during the epoch earns, including stake deactivating in that epoch.
+    /// Deactivated lamports only start activating on other validators once the deactivation
+    /// completes.
+    Effective,
+}
+
+/// Optional simulation behaviours that are toggled from the CLI
+#[derive(Clone, Debug, Default)]
+pub struct SimulationOptions {
+    /// Seed the pool with its historical TVL from `active_stake_jito_sol` instead of one SOL per
+    /// validator, and dilute each validator's rewards by the simulated pool stake.
+    pub simulate_pool_tvl: bool,
+    pub fee_model: StakePoolFeeModel,
+    pub stake_flow_attribution: StakeFlowAttribution,
+    pub unattributed_stake_flow: UnattributedStakeFlowPolicy,
+    /// Only expose the validator and cluster history that was finalized before the current
+    /// epoch, instead of the full history of the current epoch
+    pub point_in_time: bool,
+    /// Validators added to the steward blacklist, on top of the ones already in the config
+    pub blacklist: Vec<ValidatorOverride>,
+    /// Validators that are always part of the delegation set unless they are blacklisted
+    pub force_include: Vec<ValidatorOverride>,
+    /// Steward parameters to switch to at the epoch they were recorded, ordered by epoch
+    pub parameter_schedule: Vec<StewardConfigHistory>,
+    pub reward_eligibility: RewardEligibility,
+    /// Deactivate stake deposited directly on a validator, up to the steward's
+    /// `stake_deposit_unstake_cap_bps` per cycle
+    pub simulate_stake_deposit_unstake: bool,
+    pub diversity_constraints: DiversityConstraints,
+    pub allocation_mode: AllocationMode,
+    /// Most stake the allocation can deactivate in a cycle as a share of the pool, in bps,
+    /// counting the stake already deactivated by scoring changes and unstaking
+    pub max_turnover_bps: Option<u16>,
+    /// Synthetic events applied to the validator history and epoch rewards
+    pub scenario: Scenario,
+    /// Issuance the inflation rewards are rescaled to
+    pub inflation_schedule: InflationSchedule,
+    pub priority_fee_reward_model: PriorityFeeRewardModel,
+    pub skip_rate_scoring: SkipRateScoring,
 }
 
 #[derive(Clone, Debug)]
 pub struct ValidatorWithScore {
     pub vote_account: String,
     pub score: f64,
+    pub yield_score: f64,
 }
 
+#[derive(Clone)]
 pub struct RebalancingSimulator {
     pub steward_config: Config,
     pub simulation_start_epoch: u16,
@@ -48,19 +184,51 @@ pub struct RebalancingSimulator {
     pub number_of_validator_delegations: usize,
     pub instant_unstake_cap_bps: u32,
     pub scoring_unstake_cap_bps: u32,
+    pub options: SimulationOptions,
 
     pub validator_stake_states: HashMap<String, ValidatorStakeState>,
     pub validator_scores: HashMap<String, f64>,
+    pub current_cycle_start: u16,
     pub current_cycle_end: u16,
+    pub current_cycle_targets: HashMap<String, u64>,
     pub total_lamports_staked: u64,
     pub rebalancing_cycles: Vec<RebalancingCycle>,
     pub top_validators: Vec<ValidatorWithScore>,
 
     pub pending_deactivation: u64,
+    /// Undelegated lamports held in the pool reserve
+    pub reserve_lamports: u64,
+    /// Lamports of this epoch's deactivating stake that were already delegated again as
+    /// activating stake. They sit on both validators until the epoch ends.
+    pub redelegated_lamports: u64,
+    /// Every lamport movement of the simulated pool
+    pub ledger: LamportLedger,
+    pub current_epoch: u16,
     pub histories: Vec<ValidatorHistory>,
     pub jito_cluster_history: Arc<JitoClusterHistory>,
+    pub cluster_history: ClusterHistory,
+    pub cluster_history_entries: Vec<ClusterHistoryEntry>,
+    /// The point in time cluster history of the last epoch it was built for
+    pub point_in_time_cluster_history: Option<(u16, Arc<JitoClusterHistory>)>,
+    pub epoch_schedule: EpochSchedule,
     pub entries_by_validator: Arc<HashMap<String, Vec<ValidatorHistoryEntry>>>,
     pub epoch_map: HashMap<u64, Vec<EpochWithdrawDepositStakeData>>,
+    /// Active stake the JitoSOL pool actually had on each validator, by epoch
+    pub actual_pool_stakes: HashMap<u64, HashMap<String, u64>>,
+    /// Leader slots fetched into `epoch_block_production`, by validator and epoch
+    pub block_production: HashMap<String, HashMap<u16, LeaderSlots>>,
+
+    /// Simulated pool token supply, used to track the exchange rate net of fees
+    pub pool_token_supply: u64,
+    /// Pool tokens minted as fees, which don't belong to the holders we measure APY for
+    pub fee_pool_tokens: u64,
+    pub current_epoch_fees: EpochFeeRevenue,
+    pub cycle_epoch_fees: Vec<EpochFeeRevenue>,
+    pub current_cycle_turnover: CycleTurnover,
+    pub cycle_decentralization: Vec<EpochDecentralization>,
+    pub rug_exposures: Vec<RugExposure>,
+    /// Rewards of the synthetic epochs added past the recorded data, by epoch
+    pub synthetic_rewards: HashMap<u16, Vec<EpochRewards>>,
 }
 
 impl RebalancingSimulator {
@@ -75,14 +243,28 @@ impl RebalancingSimulator {
         instant_unstake_cap_bps: u32,
         scoring_unstake_cap_bps: u32,
         validator_historical_start_offset: u16,
+        options: SimulationOptions,
     ) -> Result<Self, CliError> {
         info!("Initializing rebalancing simulator...");
 
         let histories = ValidatorHistory::fetch_all(db_connection).await?;
         let cluster_history = ClusterHistory::fetch(db_connection).await?;
+        let epoch_schedule: EpochSchedule = match DbEpochSchedule::fetch(db_connection).await? {
+            Some(epoch_schedule) => epoch_schedule.into(),
+            None => {
+                warn!(
+                    "No epoch schedule recorded, run `fetch-epoch-schedule` to record it. Using the \
+                    mainnet-beta schedule without warmup"
+                );
+                EpochSchedule::without_warmup()
+            }
+        };
         let cluster_history_entries = ClusterHistoryEntry::fetch_all(db_connection).await?;
-        let jito_cluster_history =
-            Arc::new(cluster_history.convert_to_jito_cluster_history(cluster_history_entries));
+        let jito_cluster_history = Arc::new(
+            cluster_history
+                .clone()
+                .convert_to_jito_cluster_history(cluster_history_entries.clone()),
+        );
 
         info!("Fetching all validator history entries...");
         let all_entries = ValidatorHistoryEntry::fetch_all_records_between_epochs(
@@ -108,19 +290,71 @@ impl RebalancingSimulator {
         )
         .await?;
 
+        let pool_tvl_lamports = Self::pool_tvl_at_epoch(&active_stake, simulation_start_epoch);
+
+        let actual_pool_stakes = Self::build_actual_pool_stakes(
+            JitoSolValidatorStake::fetch_for_epoch_range(
+                db_connection,
+                simulation_start_epoch.into(),
+                simulation_end_epoch.into(),
+            )
+            .await?,
+        );
+
+        let block_production = if options.skip_rate_scoring.is_enabled() {
+            let vote_accounts: Vec<String> = histories
+                .iter()
+                .map(|history| history.vote_account.clone())
+                .collect();
+            Self::build_block_production(
+                EpochBlockProduction::fetch_for_vote_accounts_and_epochs(
+                    db_connection,
+                    &vote_accounts,
+                    simulation_start_epoch
+                        .saturating_sub(validator_historical_start_offset)
+                        .into(),
+                    simulation_end_epoch.into(),
+                )
+                .await?,
+            )
+        } else {
+            HashMap::new()
+        };
+
         let manual_withdraw_deposit_stake_epoch_map =
-            Self::build_epoch_map(withdraws_and_deposits_stakes, active_stake);
-        let entries_by_validator = Self::build_entries_by_validator(all_entries);
+            Self::build_epoch_map(withdraws_and_deposits_stakes, active_stake)?;
+        let mut entries_by_validator = Self::build_entries_by_validator(all_entries);
+        options.scenario.apply_to_entries(&mut entries_by_validator);
 
         info!(
             "Grouped {} validators' history entries",
             entries_by_validator.len()
         );
 
-        // start with one sol per validator
-        let total_lamports_staked = LAMPORTS_PER_SOL
-            .checked_mul(number_of_validator_delegations as u64)
-            .ok_or(CliError::ArithmeticError)?;
+        let total_lamports_staked = if options.simulate_pool_tvl {
+            if pool_tvl_lamports == 0 {
+                return Err(CliError::PoolTvlNotFound(simulation_start_epoch));
+            }
+            info!(
+                "Seeding simulation with the historical pool TVL of {:.3} SOL",
+                pool_tvl_lamports as f64 / LAMPORTS_PER_SOL as f64
+            );
+            pool_tvl_lamports
+        } else {
+            // start with one sol per validator
+            LAMPORTS_PER_SOL
+                .checked_mul(number_of_validator_delegations as u64)
+                .ok_or(CliError::ArithmeticError)?
+        };
+
+        // The pool starts with all of its lamports in the reserve
+        let mut ledger = LamportLedger::default();
+        ledger.record(
+            simulation_start_epoch,
+            LamportMovementKind::Seed,
+            None,
+            i128::from(total_lamports_staked),
+        );
 
         // Initialize validator stake states for all validators from the start
         let mut validator_stake_states = HashMap::new();
@@ -139,22 +373,82 @@ impl RebalancingSimulator {
             number_of_validator_delegations,
             instant_unstake_cap_bps,
             scoring_unstake_cap_bps,
+            options,
             validator_stake_states,
             validator_scores: HashMap::new(),
+            current_cycle_start: simulation_start_epoch,
             current_cycle_end: simulation_start_epoch
                 .checked_add(steward_cycle_rate)
                 .unwrap(),
+            current_cycle_targets: HashMap::new(),
             total_lamports_staked,
             rebalancing_cycles: Vec::new(),
             top_validators: Vec::new(),
             pending_deactivation: 0,
+            reserve_lamports: total_lamports_staked,
+            redelegated_lamports: 0,
+            ledger,
+            current_epoch: simulation_start_epoch,
             histories,
             jito_cluster_history,
+            cluster_history,
+            cluster_history_entries,
+            point_in_time_cluster_history: None,
+            epoch_schedule,
             entries_by_validator: Arc::new(entries_by_validator),
             epoch_map: manual_withdraw_deposit_stake_epoch_map,
+            actual_pool_stakes,
+            block_production,
+            pool_token_supply: total_lamports_staked,
+            fee_pool_tokens: 0,
+            current_epoch_fees: EpochFeeRevenue::default(),
+            cycle_epoch_fees: Vec::new(),
+            current_cycle_turnover: CycleTurnover::default(),
+            cycle_decentralization: Vec::new(),
+            rug_exposures: Vec::new(),
+            synthetic_rewards: HashMap::new(),
         })
     }
 
+    /// Replaces the data from `synthetic.first_epoch` on with synthetic epochs, so the simulation
+    /// can run past the recorded history. Stake flows of the replaced epochs are dropped.
+    pub fn add_synthetic_epochs(&mut self, synthetic: SyntheticEpochs) {
+        let first_epoch = synthetic.first_epoch;
+
+        let entries_by_validator = Arc::make_mut(&mut self.entries_by_validator);
+        for entries in entries_by_validator.values_mut() {
+            entries.retain(|entry| entry.validator_history_entry.epoch < first_epoch);
+        }
+        let mut synthetic_entries = Self::build_entries_by_validator(synthetic.entries);
+        self.options
+            .scenario
+            .apply_to_entries(&mut synthetic_entries);
+        for (vote_account, entries) in synthetic_entries {
+            entries_by_validator
+                .entry(vote_account)
+                .or_default()
+                .extend(entries);
+        }
+
+        self.cluster_history_entries
+            .retain(|entry| entry.epoch < first_epoch);
+        self.cluster_history_entries
+            .extend(synthetic.cluster_history_entries);
+        self.jito_cluster_history = Arc::new(
+            self.cluster_history
+                .clone()
+                .convert_to_jito_cluster_history(self.cluster_history_entries.clone()),
+        );
+        self.point_in_time_cluster_history = None;
+
+        self.epoch_map
+            .retain(|epoch, _| *epoch < u64::from(first_epoch));
+        for block_production in self.block_production.values_mut() {
+            block_production.retain(|epoch, _| *epoch < first_epoch);
+        }
+        self.synthetic_rewards = synthetic.rewards;
+    }
+
     /// Main simulation entry point
     pub async fn run_simulation(
         &mut self,
@@ -162,12 +456,30 @@ impl RebalancingSimulator {
     ) -> Result<Vec<RebalancingCycle>, CliError> {
         let mut cycle_starting_lamports = 0u64;
 
+        self.check_parameter_schedule()?;
+        if self.options.simulate_pool_tvl {
+            let fallback_epochs = self.epochs_without_validator_list();
+            if fallback_epochs > 0 {
+                warn!(
+                    "{} of {} epochs have no recorded JitoSOL validator list, their MEV and priority \
+                    fee rewards are diluted as if the pool had no stake on the validators",
+                    fallback_epochs,
+                    self.simulation_end_epoch
+                        .saturating_sub(self.simulation_start_epoch)
+                );
+            }
+        }
+
         for current_epoch in self.simulation_start_epoch..self.simulation_end_epoch {
             info!("Processing epoch {}", current_epoch);
+            self.current_epoch = current_epoch;
 
             // for all validators, put all the activating sol in the previous epoch as active and remove all the
             // deactivating sol
-            self.transition_validator_stake_stake();
+            self.transition_validator_stake_stake()?;
+            self.apply_parameter_schedule(current_epoch);
+            self.apply_blacklist(current_epoch)?;
+            self.cache_point_in_time_cluster_history(current_epoch);
 
             let is_rebalancing_epoch = self.is_rebalancing_epoch(current_epoch);
             // filter the validator entries to get only the entries that are before the current epoch
@@ -194,26 +506,162 @@ impl RebalancingSimulator {
                 )
                 .await?;
             }
+
+            self.ledger
+                .check_conservation(current_epoch, self.total_pool_lamports())?;
+            self.ledger
+                .check_rounding(current_epoch, self.validator_stake_states.len() as u64)?;
         }
 
         self.finalize_simulation(cycle_starting_lamports);
         Ok(self.rebalancing_cycles.clone())
     }
 
+    /// Number of simulated epochs without the pool's recorded delegation
+    fn epochs_without_validator_list(&self) -> usize {
+        (self.simulation_start_epoch..self.simulation_end_epoch)
+            .filter(|epoch| !self.actual_pool_stakes.contains_key(&u64::from(*epoch)))
+            .count()
+    }
+
     /// Transitions each validator's stake state. Activating stake becomes active, deactivating is
-    /// removed. 
-    fn transition_validator_stake_stake(&mut self) {
+    /// removed and the part of it that wasn't delegated again returns to the reserve.
+    fn transition_validator_stake_stake(&mut self) -> Result<(), CliError> {
+        let deactivated_lamports: u64 = self
+            .validator_stake_states
+            .values()
+            .map(|state| state.deactivating)
+            .sum();
         for stake_state in self.validator_stake_states.values_mut() {
             stake_state.process_epoch_transition();
         }
+
+        let returned_lamports = deactivated_lamports
+            .checked_sub(std::mem::take(&mut self.redelegated_lamports))
+            .ok_or(CliError::ArithmeticError)?;
+        self.reserve_lamports += returned_lamports;
+        self.ledger.record(
+            self.current_epoch,
+            LamportMovementKind::ReserveReturn,
+            None,
+            i128::from(returned_lamports),
+        );
+        Ok(())
+    }
+
+    /// Fails when the replayed steward parameters start after the simulation, which would run the
+    /// first epochs on the current config and the later ones on the recorded history
+    fn check_parameter_schedule(&self) -> Result<(), CliError> {
+        let covers_start = self
+            .options
+            .parameter_schedule
+            .iter()
+            .any(|parameters| parameters.epoch <= self.simulation_start_epoch);
+        if self.options.parameter_schedule.is_empty() || covers_start {
+            Ok(())
+        } else {
+            Err(CliError::ParameterScheduleNotFound(
+                self.simulation_start_epoch,
+            ))
+        }
+    }
+
+    /// Switches to the steward parameters that were in effect on-chain at `current_epoch`. The
+    /// number of delegated validators and the cycle length follow them, a new cycle length
+    /// taking effect from the next cycle.
+    fn apply_parameter_schedule(&mut self, current_epoch: u16) {
+        let Some(parameters) = self
+            .options
+            .parameter_schedule
+            .iter()
+            .rev()
+            .find(|parameters| parameters.epoch <= current_epoch)
+        else {
+            return;
+        };
+
+        if parameters.epoch == current_epoch || current_epoch == self.simulation_start_epoch {
+            info!(
+                "Using steward parameters recorded at epoch {} from epoch {}",
+                parameters.epoch, current_epoch
+            );
+        }
+        parameters.apply_to(&mut self.steward_config.parameters);
+        self.instant_unstake_cap_bps = self.steward_config.parameters.instant_unstake_cap_bps;
+        self.scoring_unstake_cap_bps = self.steward_config.parameters.scoring_unstake_cap_bps;
+        self.number_of_validator_delegations =
+            self.steward_config.parameters.num_delegation_validators as usize;
+        self.steward_cycle_rate =
+            u16::try_from(self.steward_config.parameters.num_epochs_between_scoring)
+                .unwrap_or(u16::MAX)
+                .max(1);
+    }
+
+    /// Adds the validators blacklisted as of `current_epoch` to the steward config blacklist so
+    /// that scoring and instant unstaking treat them the same way the program does
+    fn apply_blacklist(&mut self, current_epoch: u16) -> Result<(), CliError> {
+        for blacklisted in self
+            .options
+            .blacklist
+            .iter()
+            .filter(|blacklisted| blacklisted.is_active(current_epoch))
+        {
+            let Some(validator_history) = self
+                .histories
+                .iter()
+                .find(|vh| vh.vote_account == blacklisted.vote_account)
+            else {
+                continue;
+            };
+            self.steward_config
+                .validator_history_blacklist
+                .set(validator_history.index as usize, true)
+                .map_err(|_| {
+                    CliError::InvalidValidatorOverride(blacklisted.vote_account.clone())
+                })?;
+        }
+        Ok(())
+    }
+
+    /// Whether the validator is blacklisted at `epoch`, either in the steward config or by the
+    /// blacklist option
+    fn is_blacklisted(&self, vote_account: &str, epoch: u16) -> bool {
+        let config_blacklisted = self
+            .histories
+            .iter()
+            .find(|vh| vh.vote_account == vote_account)
+            .is_some_and(|vh| {
+                self.steward_config
+                    .validator_history_blacklist
+                    .get(vh.index as usize)
+                    .unwrap_or(false)
+            });
+        config_blacklisted
+            || self.options.blacklist.iter().any(|blacklisted| {
+                blacklisted.vote_account == vote_account && blacklisted.is_active(epoch)
+            })
+    }
+
+    /// Force included validators that are not blacklisted at `epoch`
+    fn force_included_validators(&self, epoch: u16) -> HashSet<String> {
+        self.options
+            .force_include
+            .iter()
+            .filter(|included| {
+                included.is_active(epoch) && !self.is_blacklisted(&included.vote_account, epoch)
+            })
+            .map(|included| included.vote_account.clone())
+            .collect()
     }
 
     /// checks if the current epoch is the start fo a new steward cycle
     fn is_rebalancing_epoch(&self, current_epoch: u16) -> bool {
-        (current_epoch - self.simulation_start_epoch) % self.steward_cycle_rate == 0
+        current_epoch == self.simulation_start_epoch || current_epoch >= self.current_cycle_end
     }
 
-    /// From all the validator entries, filter only the entires that are before the current epoch
+    /// From all the validator entries, filter only the entires that are before the current epoch.
+    /// In point in time mode the current epoch's entry only contains what was already
+    /// finalized when it started.
     fn get_current_epoch_entries(
         &self,
         current_epoch: u16,
@@ -222,10 +670,28 @@ impl RebalancingSimulator {
             .entries_by_validator
             .iter()
             .map(|(vote_pubkey, entries)| {
+                let previous_entry = entries
+                    .iter()
+                    .find(|entry| {
+                        u32::from(entry.validator_history_entry.epoch) + 1
+                            == u32::from(current_epoch)
+                    })
+                    .map(|entry| &entry.validator_history_entry);
                 let mut filtered_entries: Vec<ValidatorHistoryEntry> = entries
                     .iter()
                     .filter(|entry| entry.validator_history_entry.epoch <= current_epoch)
                     .cloned()
+                    .map(|mut entry| {
+                        if self.options.point_in_time
+                            && entry.validator_history_entry.epoch == current_epoch
+                        {
+                            Self::point_in_time_entry(
+                                &mut entry.validator_history_entry,
+                                previous_entry,
+                            );
+                        }
+                        entry
+                    })
                     .collect();
 
                 filtered_entries.sort_by(|a, b| {
@@ -241,6 +707,80 @@ impl RebalancingSimulator {
         Arc::new(current_epoch_entries)
     }
 
+    /// Strips an in-progress epoch's entry down to what was finalized when the epoch started.
+    /// Vote credits are taken from the previous epoch, and fields that are only uploaded after
+    /// the epoch ends are reset to their unset values.
+    fn point_in_time_entry(
+        entry: &mut JitoValidatorHistoryEntry,
+        previous_entry: Option<&JitoValidatorHistoryEntry>,
+    ) {
+        let unset = JitoValidatorHistoryEntry::default();
+        entry.epoch_credits = previous_entry.unwrap_or(&unset).epoch_credits;
+        entry.blocks_produced = unset.blocks_produced;
+        entry.mev_earned = unset.mev_earned;
+        entry.priority_fee_tips = unset.priority_fee_tips;
+        entry.total_priority_fees = unset.total_priority_fees;
+        entry.block_data_updated_at_slot = unset.block_data_updated_at_slot;
+    }
+
+    /// Builds the point in time cluster history of `current_epoch` unless it's already cached
+    fn cache_point_in_time_cluster_history(&mut self, current_epoch: u16) {
+        if !self.options.point_in_time
+            || self
+                .point_in_time_cluster_history
+                .as_ref()
+                .is_some_and(|(epoch, _)| *epoch == current_epoch)
+        {
+            return;
+        }
+        self.point_in_time_cluster_history = Some((
+            current_epoch,
+            self.build_point_in_time_cluster_history(current_epoch),
+        ));
+    }
+
+    /// Returns the cluster history as seen at `current_epoch`. Outside of point in time mode
+    /// this is the full cluster history.
+    fn cluster_history_at(&self, current_epoch: u16) -> Arc<JitoClusterHistory> {
+        if !self.options.point_in_time {
+            return Arc::clone(&self.jito_cluster_history);
+        }
+        match &self.point_in_time_cluster_history {
+            Some((epoch, cluster_history)) if *epoch == current_epoch => {
+                Arc::clone(cluster_history)
+            }
+            _ => self.build_point_in_time_cluster_history(current_epoch),
+        }
+    }
+
+    /// The cluster history up to `current_epoch`, where the in-progress epoch's block count is
+    /// the previous epoch's, matching the vote credits of the point in time validator entries
+    fn build_point_in_time_cluster_history(&self, current_epoch: u16) -> Arc<JitoClusterHistory> {
+        let previous_total_blocks = self
+            .cluster_history_entries
+            .iter()
+            .find(|entry| u32::from(entry.epoch) + 1 == u32::from(current_epoch))
+            .map(|entry| entry.total_blocks);
+        let entries: Vec<ClusterHistoryEntry> = self
+            .cluster_history_entries
+            .iter()
+            .filter(|entry| entry.epoch <= current_epoch)
+            .cloned()
+            .map(|mut entry| {
+                if entry.epoch == current_epoch {
+                    entry.total_blocks = previous_total_blocks.unwrap_or_default();
+                }
+                entry
+            })
+            .collect();
+
+        Arc::new(
+            self.cluster_history
+                .clone()
+                .convert_to_jito_cluster_history(entries),
+        )
+    }
+
     /// Starts a new steward cycle, called when a epoch is `rebalancing_epoch`
     async fn process_steward_cycle(
         &mut self,
@@ -260,11 +800,45 @@ impl RebalancingSimulator {
             self.complete_cycle(cycle_starting_lamports);
         }
 
+        let previous_validator_set: HashSet<String> = self
+            .top_validators
+            .iter()
+            .map(|validator| validator.vote_account.clone())
+            .collect();
+
         self.top_validators = self
             .select_top_validators(current_epoch_entries, current_epoch)
             .await?;
 
+        self.current_cycle_turnover.score_computations += self.histories.len() as u64;
+        // The first selection is the initial allocation rather than a change of the set
+        if !previous_validator_set.is_empty() {
+            let new_validator_set: HashSet<&String> = self
+                .top_validators
+                .iter()
+                .map(|validator| &validator.vote_account)
+                .collect();
+            self.current_cycle_turnover.validators_entered += new_validator_set
+                .iter()
+                .filter(|vote_account| !previous_validator_set.contains(**vote_account))
+                .count() as u64;
+            self.current_cycle_turnover.validators_exited += previous_validator_set
+                .iter()
+                .filter(|vote_account| !new_validator_set.contains(vote_account))
+                .count() as u64;
+        }
+
         let new_cycle_starting_lamports = self.rebalance_stakes();
+        self.current_cycle_start = current_epoch;
+        self.current_cycle_targets = self
+            .top_validators
+            .iter()
+            .filter_map(|validator| {
+                self.validator_stake_states
+                    .get(&validator.vote_account)
+                    .map(|state| (validator.vote_account.clone(), state.target))
+            })
+            .collect();
 
         self.current_cycle_end = std::cmp::min(
             current_epoch
@@ -286,6 +860,11 @@ impl RebalancingSimulator {
     ) -> Result<(), CliError> {
         // Factor in deposit/withdraws of the stakes
         self.apply_epoch_stake_changes(current_epoch)?;
+        self.detect_commission_rugs(current_epoch);
+
+        if self.options.simulate_stake_deposit_unstake && !is_rebalancing_epoch {
+            self.handle_stake_deposit_unstaking();
+        }
 
         if !self.top_validators.is_empty() && !is_rebalancing_epoch {
             self.check_previous_cycle_stake();
@@ -300,20 +879,236 @@ impl RebalancingSimulator {
         self.simulate_epoch_returns(db_connection, current_epoch)
             .await?;
 
+        let decentralization = self.decentralization_at(current_epoch);
+        self.cycle_decentralization.push(decentralization);
+        self.close_rug_exposures(current_epoch);
+
         Ok(())
     }
 
+    /// Opens a rug exposure for every delegated validator whose commission jumped above the
+    /// steward thresholds at `epoch`
+    fn detect_commission_rugs(&mut self, epoch: u16) {
+        let parameters = &self.steward_config.parameters;
+        let mut new_exposures = Vec::new();
+        for (vote_account, stake_state) in &self.validator_stake_states {
+            if stake_state.delegated() == 0
+                || self
+                    .rug_exposures
+                    .iter()
+                    .any(|exposure| exposure.is_open() && exposure.vote_account == *vote_account)
+            {
+                continue;
+            }
+            let Some(entries) = self.entries_by_validator.get(vote_account) else {
+                continue;
+            };
+            let entry_at = |epoch: u16| {
+                entries
+                    .iter()
+                    .map(|entry| &entry.validator_history_entry)
+                    .find(|entry| entry.epoch == epoch)
+            };
+            let (Some(previous), Some(current)) =
+                (entry_at(epoch.saturating_sub(1)), entry_at(epoch))
+            else {
+                continue;
+            };
+            if !is_commission_rug(
+                previous,
+                current,
+                parameters.commission_threshold,
+                parameters.mev_commission_bps_threshold,
+            ) {
+                continue;
+            }
+
+            info!(
+                "Commission rug at epoch {}: validator {} holds {:.3} SOL of pool stake (commission {}% -> {}%, MEV commission {} bps -> {} bps)",
+                epoch,
+                vote_account,
+                stake_state.delegated() as f64 / LAMPORTS_PER_SOL as f64,
+                previous.commission,
+                current.commission,
+                previous.mev_commission,
+                current.mev_commission
+            );
+            new_exposures.push(RugExposure {
+                vote_account: vote_account.clone(),
+                epoch,
+                commission_before: previous.commission,
+                commission_after: current.commission,
+                mev_commission_bps_before: previous.mev_commission,
+                mev_commission_bps_after: current.mev_commission,
+                delegated_lamports: stake_state.delegated(),
+                ..Default::default()
+            });
+        }
+        self.rug_exposures.extend(new_exposures);
+    }
+
+    /// Closes the rug exposures of validators the pool no longer delegates to
+    fn close_rug_exposures(&mut self, epoch: u16) {
+        for exposure in self.rug_exposures.iter_mut().filter(|e| e.is_open()) {
+            let delegated = self
+                .validator_stake_states
+                .get(&exposure.vote_account)
+                .map_or(0, ValidatorStakeState::delegated);
+            if delegated == 0 {
+                exposure.removed_epoch = Some(epoch);
+            }
+        }
+    }
+
+    /// The client type every validator ran at `epoch`, from its latest history entry up to it
+    fn client_types_at(&self, epoch: u16) -> HashMap<String, u8> {
+        self.entries_by_validator
+            .iter()
+            .filter_map(|(vote_account, entries)| {
+                entries
+                    .iter()
+                    .filter(|entry| entry.validator_history_entry.epoch <= epoch)
+                    .max_by_key(|entry| entry.validator_history_entry.epoch)
+                    .map(|entry| {
+                        (
+                            vote_account.clone(),
+                            entry.validator_history_entry.client_type,
+                        )
+                    })
+            })
+            .collect()
+    }
+
+    /// Every validator's skip rate over the `epoch_credits_range` epochs completed before `epoch`.
+    /// Epochs with block production fetched by the tracker use it, the others fall back to the
+    /// block production recorded in the validator history.
+    fn skip_rates_at(&self, epoch: u16) -> HashMap<String, f64> {
+        let window_start = epoch.saturating_sub(self.steward_config.parameters.epoch_credits_range);
+        let no_block_production = HashMap::new();
+        self.entries_by_validator
+            .iter()
+            .filter_map(|(vote_account, entries)| {
+                let block_production = self
+                    .block_production
+                    .get(vote_account)
+                    .unwrap_or(&no_block_production);
+                let recorded_slots = block_production
+                    .iter()
+                    .filter(|(slots_epoch, _)| {
+                        **slots_epoch >= window_start && **slots_epoch < epoch
+                    })
+                    .map(|(_, slots)| *slots);
+                let history_slots = entries
+                    .iter()
+                    .map(|entry| &entry.validator_history_entry)
+                    .filter(|entry| {
+                        entry.epoch >= window_start
+                            && entry.epoch < epoch
+                            && !block_production.contains_key(&entry.epoch)
+                    })
+                    .filter_map(LeaderSlots::from_history_entry);
+                skip_rate(recorded_slots.chain(history_slots))
+                    .map(|rate| (vote_account.clone(), rate))
+            })
+            .collect()
+    }
+
+    /// Every validator's history entry for `epoch`
+    fn entries_at(&self, epoch: u16) -> HashMap<String, JitoValidatorHistoryEntry> {
+        self.entries_by_validator
+            .iter()
+            .filter_map(|(vote_account, entries)| {
+                entries
+                    .iter()
+                    .find(|entry| entry.validator_history_entry.epoch == epoch)
+                    .map(|entry| (vote_account.clone(), entry.validator_history_entry))
+            })
+            .collect()
+    }
+
+    /// Priority fees paid per block and per slot in `epoch`, from the fees recorded for every
+    /// leader over the cluster's block count and the epoch's slots
+    async fn average_priority_fees(
+        &self,
+        db_connection: &Pool<Postgres>,
+        epoch: u16,
+    ) -> Result<AveragePriorityFees, CliError> {
+        let total_blocks = self
+            .cluster_history_entries
+            .iter()
+            .find(|entry| entry.epoch == epoch)
+            .map(|entry| entry.total_blocks)
+            .unwrap_or(0);
+        if total_blocks == 0 {
+            return Ok(AveragePriorityFees::default());
+        }
+
+        let total_priority_fees =
+            EpochPriorityFees::fetch_total_for_epoch(db_connection, u64::from(epoch)).await? as f64;
+        Ok(AveragePriorityFees {
+            per_block: total_priority_fees / f64::from(total_blocks),
+            per_slot: total_priority_fees / self.epoch_schedule.slots_per_epoch as f64,
+        })
+    }
+
+    /// Measures how decentralized the simulated delegation is at `epoch`, against the network
+    /// stake recorded in the validator history with the pool's actual stake taken out. Validators
+    /// without a history entry for the epoch are left out.
+    fn decentralization_at(&self, epoch: u16) -> EpochDecentralization {
+        let actual_pool_stakes = self.actual_pool_stakes.get(&u64::from(epoch));
+        let validators: Vec<ValidatorStakeSnapshot> = self
+            .entries_by_validator
+            .iter()
+            .filter_map(|(vote_account, entries)| {
+                let entry = &entries
+                    .iter()
+                    .find(|entry| entry.validator_history_entry.epoch == epoch)?
+                    .validator_history_entry;
+                let actual_pool_stake = actual_pool_stakes
+                    .and_then(|stakes| stakes.get(vote_account))
+                    .copied()
+                    .unwrap_or(0);
+                Some(ValidatorStakeSnapshot {
+                    pool_stake: self
+                        .validator_stake_states
+                        .get(vote_account)
+                        .map_or(0, ValidatorStakeState::delegated),
+                    network_stake: entry
+                        .activated_stake_lamports
+                        .saturating_sub(actual_pool_stake),
+                    is_superminority: entry.is_superminority == 1,
+                    client_type: entry.client_type,
+                    ip: entry.ip,
+                })
+            })
+            .collect();
+        EpochDecentralization::compute(epoch, &validators)
+    }
+
     /// stores the result of the last steward cycle in the struct and updates the total lamports staked
     fn complete_cycle(&mut self, cycle_starting_lamports: u64) {
-        let cycle_ending_lamports = self
-            .validator_stake_states
-            .values()
-            .map(|state| state.total())
-            .sum::<u64>();
+        let cycle_ending_lamports = self.total_pool_lamports();
+
+        let holder_pool_tokens = self.pool_token_supply.saturating_sub(self.fee_pool_tokens);
+        let ending_holder_lamports = if self.pool_token_supply == 0 {
+            cycle_ending_lamports
+        } else {
+            (u128::from(cycle_ending_lamports) * u128::from(holder_pool_tokens)
+                / u128::from(self.pool_token_supply)) as u64
+        };
 
         let cycle_result = RebalancingCycle {
+            start_epoch: self.current_cycle_start,
+            end_epoch: self.current_cycle_end,
+            validator_targets: std::mem::take(&mut self.current_cycle_targets),
             starting_total_lamports: cycle_starting_lamports,
             ending_total_lamports: cycle_ending_lamports,
+            ending_holder_lamports,
+            ending_holder_pool_tokens: holder_pool_tokens,
+            epoch_fees: std::mem::take(&mut self.cycle_epoch_fees),
+            turnover: std::mem::take(&mut self.current_cycle_turnover),
+            decentralization: std::mem::take(&mut self.cycle_decentralization),
+            rug_exposures: Vec::new(),
         };
 
         info!(
@@ -327,22 +1122,36 @@ impl RebalancingSimulator {
         self.total_lamports_staked = cycle_ending_lamports;
     }
 
-    /// spawns new `tokio::task` for all the validators, calculates their score
-    /// and finds the top `self.number_of_validator_delegations` validators
-    async fn select_top_validators(
+    /// Scores every validator at `epoch` with the steward parameters and blacklist in effect at
+    /// that epoch, without running the rest of the simulation
+    pub async fn score_validators_at_epoch(
+        &mut self,
+        epoch: u16,
+    ) -> Result<Vec<ValidatorWithScore>, CliError> {
+        self.check_parameter_schedule()?;
+        self.apply_parameter_schedule(epoch);
+        self.apply_blacklist(epoch)?;
+        self.cache_point_in_time_cluster_history(epoch);
+        let entries = self.get_current_epoch_entries(epoch);
+        self.score_validators(&entries, epoch).await
+    }
+
+    /// spawns new `tokio::task` for all the validators and calculates their score
+    async fn score_validators(
         &self,
         current_epoch_entries: &Arc<HashMap<String, Vec<ValidatorHistoryEntry>>>,
         current_epoch: u16,
     ) -> Result<Vec<ValidatorWithScore>, CliError> {
         info!("Scoring validators for epoch {}", current_epoch);
 
+        let cluster_history = self.cluster_history_at(current_epoch);
         let scoring_tasks: Vec<_> = self
             .histories
             .iter()
             .map(|validator_history| {
                 let validator_history = validator_history.clone();
                 let entries_by_validator = Arc::clone(current_epoch_entries);
-                let jito_cluster_history = Arc::clone(&self.jito_cluster_history);
+                let jito_cluster_history = Arc::clone(&cluster_history);
                 let steward_config = self.steward_config.clone();
 
                 tokio::task::spawn_blocking(move || {
@@ -361,33 +1170,94 @@ impl RebalancingSimulator {
             .await
             .map_err(|e| CliError::TaskJoinError(e))?;
 
-        let mut scored_validators: Vec<(String, f64)> = scoring_results
+        Ok(scoring_results
             .into_iter()
             .filter_map(|result| result.ok())
-            .collect();
+            .collect())
+    }
 
-        scored_validators.sort_by(|a, b| b.1.total_cmp(&a.1));
+    /// Scores all the validators and finds the top `self.number_of_validator_delegations`
+    /// validators
+    async fn select_top_validators(
+        &self,
+        current_epoch_entries: &Arc<HashMap<String, Vec<ValidatorHistoryEntry>>>,
+        current_epoch: u16,
+    ) -> Result<Vec<ValidatorWithScore>, CliError> {
+        let mut scored_validators = self
+            .score_validators(current_epoch_entries, current_epoch)
+            .await?;
 
-        let top_validators: Vec<ValidatorWithScore> = scored_validators
-            .into_iter()
-            .filter(|(_, score)| *score > 0.0)
-            .take(self.number_of_validator_delegations)
-            .map(|(vote_account, score)| ValidatorWithScore {
-                vote_account,
-                score,
-            })
-            .collect();
+        if self.options.skip_rate_scoring.is_enabled() {
+            let skip_rates = self.skip_rates_at(current_epoch);
+            for validator in scored_validators.iter_mut() {
+                validator.score = self.options.skip_rate_scoring.adjusted_score(
+                    validator.score,
+                    skip_rates.get(&validator.vote_account).copied(),
+                );
+            }
+        }
+
+        scored_validators.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+        // Force included validators take their spots first regardless of their score
+        let force_included = self.force_included_validators(current_epoch);
+        for included in self.options.force_include.iter().filter(|included| {
+            included.is_active(current_epoch) && !force_included.contains(&included.vote_account)
+        }) {
+            warn!(
+                "Epoch {}: force included validator {} is blacklisted and won't be selected",
+                current_epoch, included.vote_account
+            );
+        }
+        let (mut selected, others): (Vec<ValidatorWithScore>, Vec<ValidatorWithScore>) =
+            scored_validators
+                .into_iter()
+                .partition(|validator| force_included.contains(&validator.vote_account));
+        selected.truncate(self.number_of_validator_delegations);
+
+        // Force included validators count towards the diversity constraints without being
+        // subject to them
+        let validator_attributes = |vote_account: &str| {
+            current_epoch_entries
+                .get(vote_account)
+                .and_then(|entries| entries.first())
+                .map(|entry| ValidatorAttributes::from(&entry.validator_history_entry))
+        };
+        let mut diversity = DiversitySelection::new(
+            &self.options.diversity_constraints,
+            self.number_of_validator_delegations,
+        );
+        for validator in &selected {
+            if let Some(attributes) = validator_attributes(&validator.vote_account) {
+                diversity.add(&attributes);
+            }
+        }
+
+        for validator in others.into_iter().filter(|validator| validator.score > 0.0) {
+            if selected.len() >= self.number_of_validator_delegations {
+                break;
+            }
+            let attributes = validator_attributes(&validator.vote_account);
+            if let Some(constraint) = diversity.binding_constraint(attributes.as_ref()) {
+                info!(
+                    "Skipping validator {} (Score: {:.4}): {:?} constraint reached",
+                    validator.vote_account, validator.score, constraint
+                );
+                continue;
+            }
+            if let Some(attributes) = &attributes {
+                diversity.add(attributes);
+            }
+            selected.push(validator);
+        }
+        selected.sort_by(|a, b| b.score.total_cmp(&a.score));
 
-        Ok(top_validators)
+        Ok(selected)
     }
 
     /// rebalance the stakes from the validators
     fn rebalance_stakes(&mut self) -> u64 {
-        let current_total_stake = self
-            .validator_stake_states
-            .values()
-            .map(|state| state.total())
-            .sum::<u64>();
+        let current_total_stake = self.total_pool_lamports();
 
         let new_validator_set: HashSet<String> = self
             .top_validators
@@ -403,14 +1273,15 @@ impl RebalancingSimulator {
             self.total_lamports_staked
         };
 
-        self.redistribute_stakes(target_total);
+        // Lamports sitting in the reserve are delegated along with the deactivated stake
+        self.redistribute_stakes(target_total, true);
 
         target_total
     }
 
-    /// This function checks if there is still stake present in validators from the previous set 
+    /// This function checks if there is still stake present in validators from the previous set
     /// that must still be deactivated.
-    /// if yes, then we deactivate the previous amount by `self.scoring_unstake_cap_bps` and then 
+    /// if yes, then we deactivate the previous amount by `self.scoring_unstake_cap_bps` and then
     /// distribute it to the highest score validator that has not reached the `target`
     fn check_previous_cycle_stake(&mut self) {
         let new_validator_set: HashSet<String> = self
@@ -435,7 +1306,7 @@ impl RebalancingSimulator {
 
             // Redistribute to new validators
             let target_total = self.total_lamports_staked;
-            self.redistribute_stakes(target_total);
+            self.redistribute_stakes(target_total, false);
         }
     }
 
@@ -469,14 +1340,16 @@ impl RebalancingSimulator {
             if total_deactivated + total_stake <= max_deactivation_amount {
                 if let Some(stake_state) = self.validator_stake_states.get_mut(&vote_account) {
                     stake_state.target = 0;
-                    let active_to_deactivate = stake_state.active;
-                    let activating_to_deactivate = stake_state.activating;
-
-                    stake_state.deactivating += active_to_deactivate + activating_to_deactivate;
-                    stake_state.active = 0;
-                    stake_state.activating = 0;
-
-                    actual_deactivated_stake += active_to_deactivate + activating_to_deactivate;
+                    let deactivated = stake_state.deactivate_all();
+
+                    actual_deactivated_stake += deactivated;
+                    self.ledger.record(
+                        self.current_epoch,
+                        LamportMovementKind::Deactivation,
+                        Some(&vote_account),
+                        -i128::from(deactivated),
+                    );
+                    self.current_cycle_turnover.stake_operations += u64::from(deactivated > 0);
 
                     info!(
                         "Deactivating entire validator {} ({:.3} SOL) - Score: {:.4}",
@@ -487,28 +1360,20 @@ impl RebalancingSimulator {
                 }
                 total_deactivated += total_stake;
             } else if total_deactivated < max_deactivation_amount {
-                // Handle partial deactivation 
+                // Handle partial deactivation
                 let remaining_capacity = max_deactivation_amount - total_deactivated;
                 if let Some(stake_state) = self.validator_stake_states.get_mut(&vote_account) {
-                    let mut amount_to_deactivate = remaining_capacity;
-
-                    let activating_deactivation =
-                        std::cmp::min(amount_to_deactivate, stake_state.activating);
-                    stake_state.activating -= activating_deactivation;
-                    stake_state.deactivating += activating_deactivation;
-                    amount_to_deactivate -= activating_deactivation;
-
-                    if amount_to_deactivate > 0 && stake_state.active > 0 {
-                        let active_deactivation =
-                            std::cmp::min(amount_to_deactivate, stake_state.active);
-                        stake_state.active -= active_deactivation;
-                        stake_state.deactivating += active_deactivation;
-                        amount_to_deactivate -= active_deactivation;
-                    }
-
                     let total_deactivated_this_validator =
-                        remaining_capacity - amount_to_deactivate;
+                        stake_state.deactivate(remaining_capacity);
                     actual_deactivated_stake += total_deactivated_this_validator;
+                    self.ledger.record(
+                        self.current_epoch,
+                        LamportMovementKind::Deactivation,
+                        Some(&vote_account),
+                        -i128::from(total_deactivated_this_validator),
+                    );
+                    self.current_cycle_turnover.stake_operations +=
+                        u64::from(total_deactivated_this_validator > 0);
 
                     let remaining_stake = stake_state.total();
                     stake_state.target = remaining_stake;
@@ -530,6 +1395,7 @@ impl RebalancingSimulator {
         }
 
         self.pending_deactivation = actual_deactivated_stake;
+        self.current_cycle_turnover.scoring_deactivated_lamports += actual_deactivated_stake;
 
         info!(
             "Gradual migration: Deactivated {:.3} SOL ({:.2}% of total) from lowest-scored validators",
@@ -540,9 +1406,10 @@ impl RebalancingSimulator {
 
     /// This function checks if the current total of the activating stake is greater than the target
     /// if greater, unstakes the differerence, puts that in deactivating and vice versa
-    /// The amount that is distributed is the amount that has been unstaked from past validators i.e. self.pending_deactivation
+    /// The amount that is distributed is the amount that has been unstaked from past validators i.e. self.pending_deactivation,
+    /// plus the reserve when `use_reserve` is set. Deactivated stake is delegated first.
     /// Validators in top_validators that have higher score have higher priority of getting the stake first
-    fn redistribute_stakes(&mut self, target_total: u64) {
+    fn redistribute_stakes(&mut self, target_total: u64, use_reserve: bool) {
         let mut sorted_validators = self.top_validators.clone();
         sorted_validators.sort_by(|a, b| {
             b.score
@@ -550,7 +1417,27 @@ impl RebalancingSimulator {
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
 
-        let stake_per_validator: u64 = target_total / sorted_validators.len() as u64;
+        let current_stakes: HashMap<String, u64> = sorted_validators
+            .iter()
+            .map(|validator| {
+                let stake = self
+                    .validator_stake_states
+                    .get(&validator.vote_account)
+                    .map_or(0, ValidatorStakeState::delegated);
+                (validator.vote_account.clone(), stake)
+            })
+            .collect();
+        // Stake above the per-validator cap stays in the reserve
+        let targets = allocation_targets(
+            self.options.allocation_mode,
+            &sorted_validators,
+            &current_stakes,
+            target_total,
+            self.options
+                .diversity_constraints
+                .validator_stake_cap(target_total),
+            self.remaining_turnover_budget(),
+        );
 
         self.validator_scores.clear();
         for validator in &sorted_validators {
@@ -559,26 +1446,33 @@ impl RebalancingSimulator {
 
             if let Some(stake_state) = self.validator_stake_states.get_mut(&validator.vote_account)
             {
-                stake_state.target = stake_per_validator;
+                stake_state.target = targets.get(&validator.vote_account).copied().unwrap_or(0);
             }
         }
 
+        // Only the steward cycle moves stake off validators that stay in the set
+        if use_reserve && self.options.allocation_mode.reduces_stake() {
+            self.deactivate_above_targets(&sorted_validators);
+        }
+
         let total_existing_stake: u64 = self
             .validator_stake_states
             .values()
             .map(|state| state.total())
             .sum();
 
-        let available_for_redistribution = if total_existing_stake == 0 {
+        let reserve_available = if use_reserve {
+            self.reserve_lamports
+        } else {
+            0
+        };
+        let available_for_redistribution = self.pending_deactivation + reserve_available;
+        if total_existing_stake == 0 {
             info!(
                 "Initial stake allocation: distributing {:.3} SOL",
-                target_total as f64 / LAMPORTS_PER_SOL as f64
+                available_for_redistribution as f64 / LAMPORTS_PER_SOL as f64
             );
-            target_total
-        } else {
-            // Ongoing migration: use pending deactivation
-            self.pending_deactivation
-        };
+        }
 
         if available_for_redistribution == 0 {
             info!("No stake available for redistribution in this cycle");
@@ -587,6 +1481,11 @@ impl RebalancingSimulator {
 
         // Distribute available stake prioritizing high-scored validators to reach their target first
         let mut remaining_stake = available_for_redistribution;
+        let mut deactivated_stake_left = self.pending_deactivation;
+        let wait_for_deactivation = self.wait_for_deactivation();
+        // Modes that deactivate stake above the targets don't count it towards them, while the
+        // steward's equal weight delegation still counts stake until it's fully deactivated
+        let reduces_stake = self.options.allocation_mode.reduces_stake();
 
         for validator in &sorted_validators {
             if remaining_stake == 0 {
@@ -598,7 +1497,11 @@ impl RebalancingSimulator {
                 .get(&validator.vote_account)
                 .expect("Validator should exist in stake states");
 
-            let current_total = current_state.total();
+            let current_total = if reduces_stake {
+                current_state.delegated()
+            } else {
+                current_state.total()
+            };
             let desired_target = current_state.target;
 
             let needed_stake = if desired_target > current_total {
@@ -613,8 +1516,18 @@ impl RebalancingSimulator {
                     self.validator_stake_states.get_mut(&validator.vote_account)
                 {
                     stake_state.target = current_total + allocation;
-                    stake_state.add_activating_stake(allocation);
+                    let from_deactivation = allocation.min(deactivated_stake_left);
+                    deactivated_stake_left -= from_deactivation;
+                    stake_state.add_redelegated_stake(from_deactivation, wait_for_deactivation);
+                    stake_state.add_activating_stake(allocation - from_deactivation);
                     remaining_stake -= allocation;
+                    self.ledger.record(
+                        self.current_epoch,
+                        LamportMovementKind::Activation,
+                        Some(&validator.vote_account),
+                        i128::from(allocation),
+                    );
+                    self.current_cycle_turnover.stake_operations += 1;
 
                     info!(
                         "Allocating {:.3} SOL to validator {} (Score: {:.4}) - Progress: {:.1}% of desired target",
@@ -634,19 +1547,100 @@ impl RebalancingSimulator {
             }
         }
 
+        // Deactivated stake that isn't delegated again returns to the reserve at the end of the
+        // epoch
+        let allocated = available_for_redistribution - remaining_stake;
+        let from_deactivation = self.pending_deactivation - deactivated_stake_left;
+        self.redelegated_lamports += from_deactivation;
+        self.reserve_lamports -= allocated - from_deactivation;
         self.pending_deactivation = 0;
 
         info!(
-            "Redistributed {:.3} SOL to validators (target: {:.3} SOL each, remaining unfulfilled: {:.3} SOL)",
+            "Redistributed {:.3} SOL to validators (target: {:.3} SOL on average, remaining unfulfilled: {:.3} SOL)",
             (available_for_redistribution - remaining_stake) as f64 / LAMPORTS_PER_SOL as f64,
-            stake_per_validator as f64 / LAMPORTS_PER_SOL as f64,
+            targets.values().sum::<u64>() as f64 / targets.len() as f64 / LAMPORTS_PER_SOL as f64,
             remaining_stake as f64 / LAMPORTS_PER_SOL as f64
         );
     }
 
+    /// Stake that can still be deactivated this cycle under `max_turnover_bps`
+    fn remaining_turnover_budget(&self) -> Option<u64> {
+        self.options.max_turnover_bps.map(|bps| {
+            let budget = (u128::from(self.total_lamports_staked) * u128::from(bps) / 10_000)
+                .min(u128::from(u64::MAX)) as u64;
+            budget.saturating_sub(self.current_cycle_turnover.deactivated_lamports())
+        })
+    }
+
+    /// Deactivates the stake validators hold above their target, within the turnover budget,
+    /// so it can be delegated to the validators below theirs
+    fn deactivate_above_targets(&mut self, validators: &[ValidatorWithScore]) {
+        let mut budget = self.remaining_turnover_budget().unwrap_or(u64::MAX);
+        for validator in validators {
+            if budget == 0 {
+                break;
+            }
+            let Some(stake_state) = self.validator_stake_states.get_mut(&validator.vote_account)
+            else {
+                continue;
+            };
+            let excess = stake_state.delegated().saturating_sub(stake_state.target);
+            let deactivated = stake_state.deactivate(excess.min(budget));
+            if deactivated == 0 {
+                continue;
+            }
+            budget -= deactivated;
+            self.pending_deactivation += deactivated;
+
+            self.ledger.record(
+                self.current_epoch,
+                LamportMovementKind::Deactivation,
+                Some(&validator.vote_account),
+                -i128::from(deactivated),
+            );
+            self.current_cycle_turnover.stake_operations += 1;
+            self.current_cycle_turnover.rebalance_deactivated_lamports += deactivated;
+
+            info!(
+                "Deactivating {:.3} SOL above the target of validator {} (Score: {:.4})",
+                deactivated as f64 / LAMPORTS_PER_SOL as f64,
+                validator.vote_account,
+                validator.score
+            );
+        }
+    }
+
+    /// Factors in the manual withdraws and deposits of stakes for the epoch, either on random
+    /// validators or on the validators they actually happened on
+    fn apply_epoch_stake_changes(&mut self, current_epoch: u16) -> Result<(), CliError> {
+        match self.options.stake_flow_attribution {
+            StakeFlowAttribution::Random => self.apply_random_epoch_stake_changes(current_epoch)?,
+            StakeFlowAttribution::Actual => {
+                self.apply_attributed_epoch_stake_changes(current_epoch)
+            }
+        }
+
+        let unapplied_lamports = self
+            .ledger
+            .epoch_total(current_epoch, LamportMovementKind::UnappliedFlow);
+        if unapplied_lamports != 0 {
+            // The ledger reverses the flows, so a withdrawal that didn't happen is positive
+            warn!(
+                "Epoch {}: a net stake flow of {:.6} SOL couldn't be applied to the simulated pool",
+                current_epoch,
+                -unapplied_lamports as f64 / LAMPORTS_PER_SOL as f64
+            );
+        }
+
+        // updating the total lamports staked
+        self.total_lamports_staked = self.total_pool_lamports();
+
+        Ok(())
+    }
+
     /// This functions takes random validators to factor in manual withdraw and deposit of stakes
     /// The validators that are distributed are only from the top_validators array
-    fn apply_epoch_stake_changes(&mut self, current_epoch: u16) -> Result<(), CliError> {
+    fn apply_random_epoch_stake_changes(&mut self, current_epoch: u16) -> Result<(), CliError> {
         let current_epoch_u64 = current_epoch as u64;
 
         if let Some(epoch_data_vec) = self.epoch_map.get(&current_epoch_u64) {
@@ -655,17 +1649,7 @@ impl RebalancingSimulator {
                 return Ok(());
             }
 
-            // Only select from top_validators for manual withdraw/deposit stake operations
-            // also filter the ones that have a target 0
-            let top_validator_accounts: Vec<String> = self
-                .top_validators
-                .iter()
-                .filter(|v| {
-                    self.validator_stake_states.contains_key(&v.vote_account)
-                        && self.validator_stake_states[&v.vote_account].target != 0
-                })
-                .map(|v| v.vote_account.clone())
-                .collect();
+            let top_validator_accounts = self.stake_flow_validators();
 
             if top_validator_accounts.is_empty() {
                 return Ok(());
@@ -688,44 +1672,279 @@ impl RebalancingSimulator {
                 top_validator_accounts.len()
             );
 
+            let total_before_changes = self.total_pool_lamports();
+            let mut deposited_lamports = 0u64;
+            let mut withdrawn_lamports = 0u64;
+
             for (validator_account, epoch_data) in
                 selected_validators.iter().zip(epoch_data_vec.iter())
             {
                 if let Some(stake_state) = self.validator_stake_states.get_mut(validator_account) {
-                    if epoch_data.active_balance == 0.0 {
+                    if epoch_data.active_balance_lamports == 0 {
                         continue;
                     }
 
-                    let net_stake_change = epoch_data.deposit_stake - epoch_data.withdraw_stake;
-                    // calculate the ratio of the stake/unstake of that epoch to the total active balance of the epoch.
+                    // scale the stake/unstake of that epoch by the validator's share of the total active balance of the epoch.
                     // since we are using 1 Sol as a initial balance for every validator, this would be the effective stake/unstake we can do to the validator.
-                    let stake_change_ratio = net_stake_change / epoch_data.active_balance;
                     let old_active = stake_state.active;
+                    let deposit_lamports = epoch_data.scaled_deposit(old_active);
+                    let withdraw_lamports = epoch_data.scaled_withdrawal(old_active);
 
-                    stake_state.apply_stake_change(stake_change_ratio)?;
+                    let flow_change = i128::from(deposit_lamports) - i128::from(withdraw_lamports);
+                    let applied_change =
+                        i128::from(stake_state.apply_lamport_change(flow_change as i64));
                     let new_active = stake_state.active;
+                    // Only the part of the withdrawal the validator's stake could cover is charged
+                    deposited_lamports += deposit_lamports;
+                    withdrawn_lamports += withdraw_lamports - (applied_change - flow_change) as u64;
+
+                    for (kind, lamports) in [
+                        (LamportMovementKind::Deposit, i128::from(deposit_lamports)),
+                        (
+                            LamportMovementKind::Withdrawal,
+                            -i128::from(withdraw_lamports),
+                        ),
+                        (
+                            LamportMovementKind::UnappliedFlow,
+                            applied_change - flow_change,
+                        ),
+                    ] {
+                        self.ledger
+                            .record(current_epoch, kind, Some(validator_account), lamports);
+                    }
 
                     info!(
                         "Epoch {}: Adjusted validator {} active stake by {:.6} SOL ({:.2}% change) - Active: {:.6} -> {:.6} SOL",
                         current_epoch,
                         validator_account,
-                        (new_active as i64 - old_active as i64) as f64 / LAMPORTS_PER_SOL as f64,
-                        stake_change_ratio * 100.0,
+                        applied_change as f64 / LAMPORTS_PER_SOL as f64,
+                        applied_change as f64 / old_active.max(1) as f64 * 100.0,
                         old_active as f64 / LAMPORTS_PER_SOL as f64,
                         new_active as f64 / LAMPORTS_PER_SOL as f64
                     );
                 }
             }
+
+            self.apply_flow_fees(
+                total_before_changes,
+                PoolFlows {
+                    stake_deposits: deposited_lamports,
+                    stake_withdrawals: withdrawn_lamports,
+                    ..Default::default()
+                },
+            );
         }
 
-        // updating the total lamports staked
-        self.total_lamports_staked = self
-            .validator_stake_states
+        Ok(())
+    }
+
+    /// Applies each record's net stake change to the validator it actually happened on. Changes
+    /// on validators outside the simulated set, and withdrawals larger than the validator's
+    /// simulated stake, are spread according to `self.options.unattributed_stake_flow`.
+    /// Amounts are scaled by the simulated pool's size relative to the pool's actual active
+    /// balance for the epoch.
+    fn apply_attributed_epoch_stake_changes(&mut self, current_epoch: u16) {
+        let Some(epoch_data_vec) = self.epoch_map.get(&u64::from(current_epoch)) else {
+            return;
+        };
+
+        let simulated_validators = self.stake_flow_validators();
+        if epoch_data_vec.is_empty() || simulated_validators.is_empty() {
+            return;
+        }
+
+        let total_before_changes = self.total_pool_lamports();
+        let mut deposited_lamports = 0u64;
+        let mut withdrawn_lamports = 0u64;
+        let mut attributed_records = 0usize;
+        let mut unattributed_change = 0i64;
+
+        for epoch_data in epoch_data_vec {
+            if epoch_data.active_balance_lamports == 0 {
+                continue;
+            }
+
+            let deposit_lamports = epoch_data.scaled_deposit(total_before_changes);
+            let withdraw_lamports = epoch_data.scaled_withdrawal(total_before_changes);
+            deposited_lamports += deposit_lamports;
+            withdrawn_lamports += withdraw_lamports;
+            self.ledger.record(
+                current_epoch,
+                LamportMovementKind::Deposit,
+                Some(&epoch_data.vote_pubkey),
+                i128::from(deposit_lamports),
+            );
+            self.ledger.record(
+                current_epoch,
+                LamportMovementKind::Withdrawal,
+                Some(&epoch_data.vote_pubkey),
+                -i128::from(withdraw_lamports),
+            );
+
+            let net_change = deposit_lamports as i64 - withdraw_lamports as i64;
+            match self.validator_stake_states.get_mut(&epoch_data.vote_pubkey) {
+                Some(stake_state) if simulated_validators.contains(&epoch_data.vote_pubkey) => {
+                    let applied_change = stake_state.apply_lamport_change(net_change);
+                    unattributed_change += net_change - applied_change;
+                    attributed_records += 1;
+                }
+                _ => unattributed_change += net_change,
+            }
+        }
+
+        info!(
+            "Epoch {}: Applied {} of {} stake changes to the validators they happened on, spreading {:.6} SOL ({:?})",
+            current_epoch,
+            attributed_records,
+            epoch_data_vec.len(),
+            unattributed_change as f64 / LAMPORTS_PER_SOL as f64,
+            self.options.unattributed_stake_flow
+        );
+
+        let (reserve_change, unapplied_change) =
+            self.spread_unattributed_stake_change(unattributed_change, &simulated_validators);
+        // The part of the flows the reserve absorbed went through the SOL side of the pool, and
+        // the part the pool couldn't apply isn't charged
+        let sol_deposits = reserve_change.max(0) as u64;
+        let sol_withdrawals = reserve_change.min(0).unsigned_abs();
+        self.apply_flow_fees(
+            total_before_changes,
+            PoolFlows {
+                stake_deposits: deposited_lamports
+                    .saturating_sub(sol_deposits + unapplied_change.max(0) as u64),
+                stake_withdrawals: withdrawn_lamports
+                    .saturating_sub(sol_withdrawals + unapplied_change.min(0).unsigned_abs()),
+                sol_deposits,
+                sol_withdrawals,
+            },
+        );
+    }
+
+    /// Applies a net stake change that couldn't be attributed to a specific validator. Returns
+    /// the lamports that went into (positive) or were taken from (negative) the reserve, and the
+    /// part of the change that couldn't be applied at all.
+    fn spread_unattributed_stake_change(
+        &mut self,
+        mut stake_change: i64,
+        simulated_validators: &[String],
+    ) -> (i64, i64) {
+        let mut reserve_change = 0;
+        match self.options.unattributed_stake_flow {
+            UnattributedStakeFlowPolicy::ProRata => {}
+            UnattributedStakeFlowPolicy::Reserve => {
+                if stake_change >= 0 {
+                    self.reserve_lamports += stake_change as u64;
+                    return (stake_change, 0);
+                }
+                let from_reserve = self.reserve_lamports.min(stake_change.unsigned_abs());
+                self.reserve_lamports -= from_reserve;
+                stake_change += from_reserve as i64;
+                reserve_change = -(from_reserve as i64);
+            }
+            UnattributedStakeFlowPolicy::Random => {
+                if let Some(stake_state) = simulated_validators
+                    .choose(&mut rng())
+                    .and_then(|vote_account| self.validator_stake_states.get_mut(vote_account))
+                {
+                    stake_change -= stake_state.apply_lamport_change(stake_change);
+                }
+            }
+        }
+
+        let unapplied_change = if stake_change != 0 {
+            self.apply_pro_rata_stake_change(stake_change, simulated_validators)
+        } else {
+            0
+        };
+        (reserve_change, unapplied_change)
+    }
+
+    /// Spreads a net stake change across validators proportionally to their active stake. The
+    /// rounding remainder goes to the validator with the most active stake. The part of a
+    /// withdrawal larger than the validators' active stake is recorded as unapplied. Returns the
+    /// part of the change that couldn't be applied.
+    fn apply_pro_rata_stake_change(
+        &mut self,
+        stake_change: i64,
+        simulated_validators: &[String],
+    ) -> i64 {
+        let total_active: u64 = simulated_validators
+            .iter()
+            .filter_map(|vote_account| self.validator_stake_states.get(vote_account))
+            .map(|state| state.active)
+            .sum();
+        if total_active == 0 {
+            self.ledger.record(
+                self.current_epoch,
+                LamportMovementKind::UnappliedFlow,
+                None,
+                -i128::from(stake_change),
+            );
+            return stake_change;
+        }
+
+        let mut unapplied_change = 0;
+        let mut remainder = stake_change;
+        for vote_account in simulated_validators {
+            if let Some(stake_state) = self.validator_stake_states.get_mut(vote_account) {
+                let share = (i128::from(stake_change) * i128::from(stake_state.active)
+                    / i128::from(total_active)) as i64;
+                remainder -= share;
+                unapplied_change += share - stake_state.apply_lamport_change(share);
+            }
+        }
+
+        if let Some(vote_account) = simulated_validators
+            .iter()
+            .max_by_key(|vote_account| self.validator_stake_states[*vote_account].active)
+        {
+            if let Some(stake_state) = self.validator_stake_states.get_mut(vote_account) {
+                remainder -= stake_state.apply_lamport_change(remainder);
+            }
+        }
+        self.ledger.record(
+            self.current_epoch,
+            LamportMovementKind::UnappliedFlow,
+            None,
+            -i128::from(unapplied_change),
+        );
+        // Less than a lamport per validator is left when the largest validator can't take it
+        self.ledger.record(
+            self.current_epoch,
+            LamportMovementKind::Rounding,
+            None,
+            -i128::from(remainder),
+        );
+        unapplied_change + remainder
+    }
+
+    /// Whether deactivated lamports have to finish deactivating before they can be delegated again
+    fn wait_for_deactivation(&self) -> bool {
+        self.options.reward_eligibility == RewardEligibility::Effective
+    }
+
+    /// Only select from top_validators for manual withdraw/deposit stake operations
+    /// also filter the ones that have a target 0
+    fn stake_flow_validators(&self) -> Vec<String> {
+        self.top_validators
+            .iter()
+            .filter(|v| {
+                self.validator_stake_states.contains_key(&v.vote_account)
+                    && self.validator_stake_states[&v.vote_account].target != 0
+            })
+            .map(|v| v.vote_account.clone())
+            .collect()
+    }
+
+    /// Total lamports in the simulated pool, delegated or held in the reserve. Deactivating stake
+    /// that was already delegated again is only counted once.
+    fn total_pool_lamports(&self) -> u64 {
+        self.validator_stake_states
             .values()
             .map(|state| state.total())
-            .sum::<u64>();
-
-        Ok(())
+            .sum::<u64>()
+            + self.reserve_lamports
+            - self.redelegated_lamports
     }
 
     /// Calculate the validators that need to be unstaked in an epoch and then unstakes them
@@ -734,12 +1953,16 @@ impl RebalancingSimulator {
         current_epoch_entries: &Arc<HashMap<String, Vec<ValidatorHistoryEntry>>>,
         current_epoch: u16,
     ) -> Result<(), CliError> {
+        // Force included validators are never instantly unstaked
+        let force_included = self.force_included_validators(current_epoch);
         let current_validator_list: Vec<String> = self
             .top_validators
             .iter()
             .map(|v| v.vote_account.clone())
+            .filter(|vote_account| !force_included.contains(vote_account))
             .collect();
 
+        self.current_cycle_turnover.instant_unstake_checks += current_validator_list.len() as u64;
         let validators_to_unstake = self
             .calculate_unstake_per_epoch(
                 &current_validator_list,
@@ -763,7 +1986,10 @@ impl RebalancingSimulator {
         entries_by_validator: &Arc<HashMap<String, Vec<ValidatorHistoryEntry>>>,
         epoch: u16,
     ) -> Result<Vec<String>, CliError> {
-        let epoch_start_slot = epoch as u64 * 432_000;
+        let epoch_start_slot = self
+            .epoch_schedule
+            .get_first_slot_in_epoch(u64::from(epoch));
+        let cluster_history = self.cluster_history_at(epoch);
         let unstake_tasks: Vec<_> = selected_validators
             .iter()
             .filter_map(|validator_vote_account| {
@@ -773,7 +1999,7 @@ impl RebalancingSimulator {
                     .map(|validator_history| {
                         let validator_history = validator_history.clone();
                         let entries_by_validator = Arc::clone(entries_by_validator);
-                        let jito_cluster_history = Arc::clone(&self.jito_cluster_history);
+                        let jito_cluster_history = Arc::clone(&cluster_history);
                         let steward_config = self.steward_config.clone();
                         let vote_account = validator_vote_account.clone();
 
@@ -861,13 +2087,28 @@ impl RebalancingSimulator {
             }
         }
 
-        // Deactivate stake from unstaked validators
+        // Deactivate stake from unstaked validators. Stake that was already deactivating has
+        // been accounted for and isn't delegated again.
+        let mut newly_deactivated_amount = 0u64;
         for validator in &actual_validators_to_unstake {
+            for exposure in self
+                .rug_exposures
+                .iter_mut()
+                .filter(|exposure| exposure.is_open() && exposure.vote_account == *validator)
+            {
+                exposure.instantly_unstaked = true;
+            }
             if let Some(stake_state) = self.validator_stake_states.get_mut(validator) {
                 let total_stake = stake_state.total();
-                stake_state.add_deactivating_stake(stake_state.active)?;
-                stake_state.deactivating += stake_state.activating;
-                stake_state.activating = 0;
+                let deactivated = stake_state.deactivate_all();
+                newly_deactivated_amount += deactivated;
+                self.ledger.record(
+                    self.current_epoch,
+                    LamportMovementKind::Deactivation,
+                    Some(validator),
+                    -i128::from(deactivated),
+                );
+                self.current_cycle_turnover.stake_operations += u64::from(deactivated > 0);
                 // Set target to 0 for instantly unstaked validators
                 stake_state.target = 0;
 
@@ -879,16 +2120,74 @@ impl RebalancingSimulator {
             }
         }
 
+        self.current_cycle_turnover
+            .instant_unstake_deactivated_lamports += newly_deactivated_amount;
+        self.current_cycle_turnover.validators_instantly_unstaked +=
+            actual_validators_to_unstake.len() as u64;
+
         // Redistribute to remaining validators
-        if total_unstaked_amount > 0 {
-            self.redistribute_unstaked_amount(total_unstaked_amount, &actual_validators_to_unstake);
+        if newly_deactivated_amount > 0 {
+            self.redistribute_unstaked_amount(
+                newly_deactivated_amount,
+                &actual_validators_to_unstake,
+            );
         }
 
         Ok(())
     }
 
+    /// Deactivates the stake deposited directly on validators this epoch, lowest scored validators
+    /// first, until `stake_deposit_unstake_cap_bps` of the pool has been deactivated in the
+    /// cycle. The lamports return to the reserve and are delegated at the next steward cycle.
+    fn handle_stake_deposit_unstaking(&mut self) {
+        let cycle_cap = (u128::from(self.total_lamports_staked)
+            * u128::from(self.steward_config.parameters.stake_deposit_unstake_cap_bps)
+            / 10_000)
+            .min(u128::from(u64::MAX)) as u64;
+        let mut remaining_cap = cycle_cap.saturating_sub(
+            self.current_cycle_turnover
+                .stake_deposit_deactivated_lamports,
+        );
+
+        let mut validators = self.top_validators.clone();
+        validators.sort_by(|a, b| a.score.total_cmp(&b.score));
+        for validator in validators {
+            if remaining_cap == 0 {
+                break;
+            }
+            let Some(stake_state) = self.validator_stake_states.get_mut(&validator.vote_account)
+            else {
+                continue;
+            };
+            let deactivated = stake_state.deactivate(stake_state.epoch_deposits.min(remaining_cap));
+            if deactivated == 0 {
+                continue;
+            }
+            stake_state.epoch_deposits -= deactivated;
+            remaining_cap -= deactivated;
+
+            self.ledger.record(
+                self.current_epoch,
+                LamportMovementKind::Deactivation,
+                Some(&validator.vote_account),
+                -i128::from(deactivated),
+            );
+            self.current_cycle_turnover.stake_operations += 1;
+            self.current_cycle_turnover
+                .stake_deposit_deactivated_lamports += deactivated;
+
+            info!(
+                "Stake deposit unstaking: moved {:.3} SOL to deactivating for validator {}",
+                deactivated as f64 / LAMPORTS_PER_SOL as f64,
+                validator.vote_account
+            );
+        }
+    }
+
     /// Use the unstaked amount in the rebalancing and put it to the remaining validators.
     /// Amount isn't actually active yet as it will be activated in the next epoch. It will be added in the activating stake for that epoch
+    /// The remainder of the division, or the whole amount when no validators remain, returns to
+    /// the reserve at the end of the epoch.
     fn redistribute_unstaked_amount(
         &mut self,
         total_unstaked_amount: u64,
@@ -901,6 +2200,7 @@ impl RebalancingSimulator {
             .collect();
 
         if !remaining_validators.is_empty() {
+            let wait_for_deactivation = self.wait_for_deactivation();
             let stake_per_remaining_validator =
                 total_unstaked_amount / remaining_validators.len() as u64;
 
@@ -908,9 +2208,21 @@ impl RebalancingSimulator {
                 if let Some(stake_state) =
                     self.validator_stake_states.get_mut(&validator.vote_account)
                 {
-                    stake_state.add_activating_stake(stake_per_remaining_validator);
+                    stake_state.add_redelegated_stake(
+                        stake_per_remaining_validator,
+                        wait_for_deactivation,
+                    );
                     // Update target to reflect the additional stake
                     stake_state.target += stake_per_remaining_validator;
+                    self.redelegated_lamports += stake_per_remaining_validator;
+                    self.ledger.record(
+                        self.current_epoch,
+                        LamportMovementKind::Activation,
+                        Some(&validator.vote_account),
+                        i128::from(stake_per_remaining_validator),
+                    );
+                    self.current_cycle_turnover.stake_operations +=
+                        u64::from(stake_per_remaining_validator > 0);
                 }
             }
 
@@ -930,37 +2242,95 @@ impl RebalancingSimulator {
         db_connection: &Pool<Postgres>,
         current_epoch: u16,
     ) -> Result<(), CliError> {
-        let total_before_rewards = self
-            .validator_stake_states
-            .values()
-            .map(|state| state.total())
-            .sum::<u64>();
+        let total_before_rewards = self.total_pool_lamports();
 
         let validator_list: Vec<String> = self.validator_stake_states.keys().cloned().collect();
-        let rewards = EpochRewards::fetch_for_single_epoch(
-            db_connection,
-            &validator_list,
-            current_epoch.into(),
-        )
-        .await?;
+        let mut rewards = match self.synthetic_rewards.remove(&current_epoch) {
+            Some(rewards) => rewards,
+            None => {
+                EpochRewards::fetch_for_single_epoch(
+                    db_connection,
+                    &validator_list,
+                    current_epoch.into(),
+                )
+                .await?
+            }
+        };
+        self.options.inflation_schedule.apply_to_rewards(
+            current_epoch,
+            self.epoch_schedule.slots_per_epoch,
+            &mut rewards,
+        );
+        if self.options.priority_fee_reward_model != PriorityFeeRewardModel::Recorded {
+            let average_fees = self
+                .average_priority_fees(db_connection, current_epoch)
+                .await?;
+            self.options.priority_fee_reward_model.apply_to_rewards(
+                &mut rewards,
+                &self.entries_at(current_epoch),
+                average_fees,
+            );
+        }
+        if !self.options.scenario.events.is_empty() {
+            let client_types = self.client_types_at(current_epoch);
+            self.options
+                .scenario
+                .apply_to_rewards(current_epoch, &mut rewards, &client_types);
+        }
 
-        for reward in rewards {
-            if let Some(stake_state) = self.validator_stake_states.get_mut(&reward.vote_pubkey) {
-                if stake_state.active > 0 {
-                    let reward_amount =
-                        reward.stake_after_epoch(stake_state.active) - stake_state.active;
-                    stake_state.apply_rewards(reward_amount);
+        for mut reward in rewards {
+            if let Some(stake_state) = self.validator_stake_states.get(&reward.vote_pubkey) {
+                let earning_deactivating = match self.options.reward_eligibility {
+                    RewardEligibility::ActiveOnly => 0,
+                    RewardEligibility::Effective => stake_state.deactivating,
+                };
+                let effective_stake = stake_state.active + earning_deactivating;
+
+                // Stake in transition doesn't earn, estimate what it would have at this
+                // validator's rate
+                let transitioning_stake = stake_state.activating
+                    + stake_state.pending_activation
+                    + (stake_state.deactivating - earning_deactivating);
+                self.current_cycle_turnover.forgone_rewards_lamports +=
+                    rewards_at_validator_rate(&reward, transitioning_stake);
+
+                if effective_stake > 0 {
+                    let stake_after_epoch =
+                        self.stake_after_epoch(&reward, effective_stake, current_epoch);
+                    // Deactivating stake's share of the rewards leaves the validator with it
+                    let reward_amount = stake_after_epoch - effective_stake;
+
+                    if let Some(exposure_index) = self.rug_exposures.iter().position(|exposure| {
+                        exposure.is_open() && exposure.vote_account == reward.vote_pubkey
+                    }) {
+                        let (commission_bps, mev_commission_bps) =
+                            self.rug_exposures[exposure_index].commission_bps_before();
+                        reward.inflation_commission_bps =
+                            reward.inflation_commission_bps.min(commission_bps);
+                        reward.mev_commission_bps =
+                            reward.mev_commission_bps.min(mev_commission_bps);
+                        let stake_after_epoch_without_rug =
+                            self.stake_after_epoch(&reward, effective_stake, current_epoch);
+                        self.rug_exposures[exposure_index].rewards_lost_lamports +=
+                            stake_after_epoch_without_rug.saturating_sub(stake_after_epoch);
+                    }
+
+                    let deactivating_reward =
+                        (u128::from(reward_amount) * u128::from(earning_deactivating)
+                            / u128::from(effective_stake)) as u64;
+                    self.apply_reward(
+                        &reward.vote_pubkey,
+                        reward_amount - deactivating_reward,
+                        deactivating_reward,
+                    );
                 }
             }
         }
 
-        let total_after_rewards = self
-            .validator_stake_states
-            .values()
-            .map(|state| state.total())
-            .sum::<u64>();
+        let total_after_rewards = self.total_pool_lamports();
 
         self.total_lamports_staked = total_after_rewards;
+        self.apply_epoch_fee(current_epoch, total_after_rewards - total_before_rewards);
 
         let active_stake_total = self
             .validator_stake_states
@@ -980,6 +2350,89 @@ impl RebalancingSimulator {
         Ok(())
     }
 
+    /// Stake after the epoch's rewards are paid on `effective_stake` delegated to the validator
+    fn stake_after_epoch(
+        &self,
+        reward: &EpochRewards,
+        effective_stake: u64,
+        current_epoch: u16,
+    ) -> u64 {
+        if self.options.simulate_pool_tvl {
+            // The validator's recorded active stake includes what the pool actually delegated to
+            // it, which is swapped for the simulated stake. Epochs without a recorded validator
+            // list assume the pool had no stake on the validator, so the simulated stake always
+            // dilutes the fixed rewards.
+            let actual_pool_stake = self
+                .actual_pool_stakes
+                .get(&u64::from(current_epoch))
+                .and_then(|stakes| stakes.get(&reward.vote_pubkey).copied())
+                .unwrap_or(0);
+            reward.stake_after_epoch_with_dilution(effective_stake, actual_pool_stake)
+        } else {
+            reward.stake_after_epoch(effective_stake)
+        }
+    }
+
+    /// Adds an epoch's rewards to a validator's active and deactivating stake
+    fn apply_reward(
+        &mut self,
+        vote_account: &str,
+        active_reward_lamports: u64,
+        deactivating_reward_lamports: u64,
+    ) {
+        if let Some(stake_state) = self.validator_stake_states.get_mut(vote_account) {
+            stake_state.apply_rewards(active_reward_lamports);
+            stake_state.apply_deactivating_rewards(deactivating_reward_lamports);
+            self.ledger.record(
+                self.current_epoch,
+                LamportMovementKind::Reward,
+                Some(vote_account),
+                i128::from(active_reward_lamports + deactivating_reward_lamports),
+            );
+        }
+    }
+
+    /// Charges the deposit and withdrawal fees on this epoch's flows. The pool token supply
+    /// is scaled with the pool's lamports so the exchange rate isn't affected by the flows, and
+    /// the fee share of the minted tokens is attributed to the manager and referrers.
+    fn apply_flow_fees(&mut self, total_before_changes: u64, flows: PoolFlows) {
+        let total_after_changes = self.total_pool_lamports();
+        if total_before_changes == 0 || total_after_changes == 0 {
+            return;
+        }
+
+        self.pool_token_supply = (u128::from(self.pool_token_supply)
+            * u128::from(total_after_changes)
+            / u128::from(total_before_changes)) as u64;
+
+        let (deposit_fee, withdrawal_fee, referral_fee) = self.options.fee_model.flow_fees(&flows);
+        let fee_pool_tokens = u128::from(deposit_fee + withdrawal_fee)
+            * u128::from(self.pool_token_supply)
+            / u128::from(total_after_changes);
+
+        self.fee_pool_tokens += fee_pool_tokens as u64;
+        self.current_epoch_fees.deposit_fee += deposit_fee;
+        self.current_epoch_fees.withdrawal_fee += withdrawal_fee;
+        self.current_epoch_fees.referral_fee += referral_fee;
+    }
+
+    /// Mints the epoch fee on this epoch's rewards and records the epoch's fee revenue
+    fn apply_epoch_fee(&mut self, current_epoch: u16, gross_rewards: u64) {
+        let fee_pool_tokens = self.options.fee_model.epoch_fee_pool_tokens(
+            gross_rewards,
+            self.total_lamports_staked,
+            self.pool_token_supply,
+        );
+        self.pool_token_supply += fee_pool_tokens;
+        self.fee_pool_tokens += fee_pool_tokens;
+
+        let mut epoch_fees = std::mem::take(&mut self.current_epoch_fees);
+        epoch_fees.epoch = current_epoch;
+        epoch_fees.gross_rewards = gross_rewards;
+        epoch_fees.epoch_fee = self.options.fee_model.epoch_fee(gross_rewards);
+        self.cycle_epoch_fees.push(epoch_fees);
+    }
+
     /// Pushes the final rebalancing cycle
     fn finalize_simulation(&mut self, cycle_starting_lamports: u64) {
         // Always complete the final cycle if we have validator states
@@ -987,6 +2440,18 @@ impl RebalancingSimulator {
             self.complete_cycle(cycle_starting_lamports);
         }
 
+        // Exposures can outlast the cycle of their rug, so they're only assigned once final
+        for exposure in std::mem::take(&mut self.rug_exposures) {
+            if let Some(cycle) = self
+                .rebalancing_cycles
+                .iter_mut()
+                .rev()
+                .find(|cycle| cycle.start_epoch <= exposure.epoch)
+            {
+                cycle.rug_exposures.push(exposure);
+            }
+        }
+
         info!(
             "Simulation completed with {} rebalancing cycles",
             self.rebalancing_cycles.len()
@@ -1007,32 +2472,97 @@ impl RebalancingSimulator {
         entries_by_validator
     }
 
+    /// Groups the fetched block production by validator and epoch
+    fn build_block_production(
+        block_production: Vec<VoteAccountBlockProduction>,
+    ) -> HashMap<String, HashMap<u16, LeaderSlots>> {
+        let mut by_validator: HashMap<String, HashMap<u16, LeaderSlots>> = HashMap::new();
+        for production in block_production {
+            by_validator
+                .entry(production.vote_pubkey.clone())
+                .or_default()
+                .insert(
+                    production.epoch as u16,
+                    LeaderSlots::from_block_production(&production),
+                );
+        }
+        by_validator
+    }
+
+    /// Groups the pool's recorded active stake per validator by epoch
+    fn build_actual_pool_stakes(
+        validator_stakes: Vec<JitoSolValidatorStake>,
+    ) -> HashMap<u64, HashMap<String, u64>> {
+        let mut actual_pool_stakes: HashMap<u64, HashMap<String, u64>> = HashMap::new();
+        for stake in validator_stakes {
+            actual_pool_stakes
+                .entry(stake.epoch)
+                .or_default()
+                .insert(stake.vote_pubkey, stake.active_stake_lamports);
+        }
+        actual_pool_stakes
+    }
+
+    /// Returns the pool's active stake at `epoch` in lamports, falling back to the earliest
+    /// recorded epoch after it when the epoch itself is missing.
+    fn pool_tvl_at_epoch(active_stake: &[ActiveStakeJitoSol], epoch: u16) -> u64 {
+        active_stake
+            .iter()
+            .filter(|stake| stake.epoch >= u64::from(epoch))
+            .min_by_key(|stake| stake.epoch)
+            .and_then(|stake| Self::sol_to_lamports(&stake.balance))
+            .unwrap_or(0)
+    }
+
+    /// Converts a SOL amount to lamports, rounding to the nearest lamport. Returns `None` for
+    /// negative amounts and amounts that don't fit in a `u64`.
+    fn sol_to_lamports(sol: &BigDecimal) -> Option<u64> {
+        (sol * BigDecimal::from(LAMPORTS_PER_SOL)).round(0).to_u64()
+    }
+
     /// This returns the hashap of manual withdraws and deposits of stakes epochwise
+    /// Amounts are converted to lamports so the stake changes are applied exactly
     fn build_epoch_map(
         withdraws_and_deposits: Vec<WithdrawsAndDeposits>,
         active_stake: Vec<ActiveStakeJitoSol>,
-    ) -> HashMap<u64, Vec<EpochWithdrawDepositStakeData>> {
+    ) -> Result<HashMap<u64, Vec<EpochWithdrawDepositStakeData>>, CliError> {
         let mut epoch_map: HashMap<u64, Vec<EpochWithdrawDepositStakeData>> = HashMap::new();
-        let mut active_by_epoch: HashMap<u64, f64> = HashMap::new();
+        let mut active_by_epoch: HashMap<u64, u64> = HashMap::new();
 
         for stake in active_stake {
-            let balance = stake.balance.to_f64().unwrap_or(0.0);
-            *active_by_epoch.entry(stake.epoch).or_insert(0.0) += balance;
+            let balance = Self::sol_to_lamports(&stake.balance).ok_or_else(|| {
+                CliError::InvalidStakeAmount(format!(
+                    "active stake of {} SOL at epoch {}",
+                    stake.balance, stake.epoch
+                ))
+            })?;
+            *active_by_epoch.entry(stake.epoch).or_insert(0) += balance;
         }
 
         for wd in withdraws_and_deposits {
-            let active_balance = active_by_epoch.get(&wd.epoch).cloned().unwrap_or(0.0);
+            let active_balance_lamports = active_by_epoch.get(&wd.epoch).copied().unwrap_or(0);
+            let to_lamports = |sol: &BigDecimal, kind: &str| {
+                Self::sol_to_lamports(sol).ok_or_else(|| {
+                    CliError::InvalidStakeAmount(format!(
+                        "{} of {} SOL on {} at epoch {}",
+                        kind, sol, wd.vote_pubkey, wd.epoch
+                    ))
+                })
+            };
+            let withdraw_lamports = to_lamports(&wd.withdraw_stake, "withdrawal")?;
+            let deposit_lamports = to_lamports(&wd.deposit_stake, "deposit")?;
 
             epoch_map.entry(wd.epoch).or_insert_with(Vec::new).push(
                 EpochWithdrawDepositStakeData {
-                    withdraw_stake: wd.withdraw_stake.to_f64().unwrap_or(0.0),
-                    deposit_stake: wd.deposit_stake.to_f64().unwrap_or(0.0),
-                    active_balance,
+                    vote_pubkey: wd.vote_pubkey,
+                    withdraw_lamports,
+                    deposit_lamports,
+                    active_balance_lamports,
                 },
             );
         }
 
-        epoch_map
+        Ok(epoch_map)
     }
 
     fn score_validator(
@@ -1041,7 +2571,7 @@ impl RebalancingSimulator {
         jito_cluster_history: &JitoClusterHistory,
         steward_config: &Config,
         current_epoch: u16,
-    ) -> Result<(String, f64), CliError> {
+    ) -> Result<ValidatorWithScore, CliError> {
         let vote_account = validator_history.vote_account.clone();
 
         let mut entries = entries_by_validator
@@ -1061,8 +2591,16 @@ impl RebalancingSimulator {
         );
 
         match score_result {
-            Ok(score) => Ok((vote_account, score.score)),
-            Err(_) => Ok((vote_account, 0.0)),
+            Ok(score) => Ok(ValidatorWithScore {
+                vote_account,
+                score: score.score,
+                yield_score: score.yield_score,
+            }),
+            Err(_) => Ok(ValidatorWithScore {
+                vote_account,
+                score: 0.0,
+                yield_score: 0.0,
+            }),
         }
     }
 
@@ -1104,3 +2642,869 @@ impl RebalancingSimulator {
         }
     }
 }
+
+/// Rewards `stake` would have earned at the validator's per-lamport rate for the epoch. Unlike
+/// `EpochRewards::stake_after_epoch`, `stake` may exceed the validator's active stake.
+fn rewards_at_validator_rate(reward: &EpochRewards, stake: u64) -> u64 {
+    if reward.active_stake == 0 || stake == 0 {
+        return 0;
+    }
+    let validator_rewards = reward.stake_after_epoch(reward.active_stake) - reward.active_stake;
+    (u128::from(validator_rewards) * u128::from(stake) / u128::from(reward.active_stake)) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{calculate_aggregated_apy, calculate_net_aggregated_apy};
+    use bytemuck::Zeroable;
+    use proptest::prelude::*;
+    use stakenet_simulator_db::epoch_rewards::test_epoch_rewards;
+    use std::collections::BTreeSet;
+
+    const NUM_VALIDATORS: usize = 8;
+
+    fn vote_account(index: usize) -> String {
+        format!("validator-{index}")
+    }
+
+    fn test_simulator(
+        seed_lamports: u64,
+        unattributed_stake_flow: UnattributedStakeFlowPolicy,
+        reward_eligibility: RewardEligibility,
+    ) -> RebalancingSimulator {
+        let mut ledger = LamportLedger::default();
+        ledger.record(
+            0,
+            LamportMovementKind::Seed,
+            None,
+            i128::from(seed_lamports),
+        );
+
+        RebalancingSimulator {
+            steward_config: Config::zeroed(),
+            simulation_start_epoch: 0,
+            simulation_end_epoch: 100,
+            steward_cycle_rate: 10,
+            number_of_validator_delegations: NUM_VALIDATORS,
+            instant_unstake_cap_bps: 1_000,
+            scoring_unstake_cap_bps: 1_000,
+            options: SimulationOptions {
+                stake_flow_attribution: StakeFlowAttribution::Actual,
+                unattributed_stake_flow,
+                reward_eligibility,
+                ..Default::default()
+            },
+            validator_stake_states: (0..NUM_VALIDATORS)
+                .map(|index| (vote_account(index), ValidatorStakeState::default()))
+                .collect(),
+            validator_scores: HashMap::new(),
+            current_cycle_start: 0,
+            current_cycle_end: 10,
+            current_cycle_targets: HashMap::new(),
+            total_lamports_staked: seed_lamports,
+            rebalancing_cycles: Vec::new(),
+            top_validators: Vec::new(),
+            pending_deactivation: 0,
+            reserve_lamports: seed_lamports,
+            redelegated_lamports: 0,
+            ledger,
+            current_epoch: 0,
+            histories: Vec::new(),
+            jito_cluster_history: Arc::new(JitoClusterHistory::zeroed()),
+            cluster_history: ClusterHistory {
+                struct_version: 0,
+                bump: 0,
+                cluster_history_last_update_slot: 0,
+            },
+            cluster_history_entries: Vec::new(),
+            point_in_time_cluster_history: None,
+            epoch_schedule: EpochSchedule::default(),
+            entries_by_validator: Arc::new(HashMap::new()),
+            epoch_map: HashMap::new(),
+            actual_pool_stakes: HashMap::new(),
+            block_production: HashMap::new(),
+            pool_token_supply: seed_lamports,
+            fee_pool_tokens: 0,
+            current_epoch_fees: EpochFeeRevenue::default(),
+            cycle_epoch_fees: Vec::new(),
+            current_cycle_turnover: CycleTurnover::default(),
+            cycle_decentralization: Vec::new(),
+            rug_exposures: Vec::new(),
+            synthetic_rewards: HashMap::new(),
+        }
+    }
+
+    fn select_validators(simulator: &mut RebalancingSimulator, validators: &[usize]) {
+        simulator.top_validators = validators
+            .iter()
+            .map(|index| ValidatorWithScore {
+                vote_account: vote_account(*index),
+                score: 1.0 - *index as f64 / NUM_VALIDATORS as f64,
+                yield_score: 0.0,
+            })
+            .collect();
+    }
+
+    /// A simulator with the given validators delegated to and charging 100 bps on stake flows
+    /// and 200 bps on SOL flows
+    fn funded_simulator(
+        stake_flow_attribution: StakeFlowAttribution,
+        unattributed_stake_flow: UnattributedStakeFlowPolicy,
+        active_stakes: &[(usize, u64)],
+        reserve_lamports: u64,
+    ) -> RebalancingSimulator {
+        let total = active_stakes.iter().map(|(_, active)| active).sum::<u64>() + reserve_lamports;
+        let mut simulator = test_simulator(
+            total,
+            unattributed_stake_flow,
+            RewardEligibility::ActiveOnly,
+        );
+        simulator.options.stake_flow_attribution = stake_flow_attribution;
+        simulator.options.fee_model = StakePoolFeeModel {
+            stake_deposit_fee_bps: 100,
+            stake_withdrawal_fee_bps: 100,
+            sol_deposit_fee_bps: 200,
+            sol_withdrawal_fee_bps: 200,
+            ..Default::default()
+        };
+        simulator.reserve_lamports = reserve_lamports;
+        for (index, active) in active_stakes {
+            let stake_state = simulator
+                .validator_stake_states
+                .get_mut(&vote_account(*index))
+                .unwrap();
+            stake_state.active = *active;
+            stake_state.target = *active;
+        }
+        let validators: Vec<usize> = active_stakes.iter().map(|(index, _)| *index).collect();
+        select_validators(&mut simulator, &validators);
+        simulator
+    }
+
+    fn record_stake_flow(
+        simulator: &mut RebalancingSimulator,
+        validator: usize,
+        deposit_lamports: u64,
+        withdraw_lamports: u64,
+    ) {
+        let active_balance_lamports = simulator.total_pool_lamports();
+        simulator
+            .epoch_map
+            .entry(u64::from(simulator.current_epoch))
+            .or_default()
+            .push(EpochWithdrawDepositStakeData {
+                vote_pubkey: vote_account(validator),
+                withdraw_lamports,
+                deposit_lamports,
+                active_balance_lamports,
+            });
+    }
+
+    #[derive(Clone, Debug)]
+    enum Operation {
+        Rebalance(Vec<usize>),
+        ContinueMigration,
+        InstantUnstake(Vec<usize>),
+        StakeFlow {
+            validator: usize,
+            deposit: u64,
+            withdraw: u64,
+        },
+        Reward {
+            validator: usize,
+            lamports: u64,
+        },
+        EpochTransition,
+    }
+
+    fn validator_set(max_size: usize) -> impl Strategy<Value = Vec<usize>> {
+        prop::collection::btree_set(0..NUM_VALIDATORS, 1..=max_size)
+            .prop_map(|set: BTreeSet<usize>| set.into_iter().collect())
+    }
+
+    fn operation() -> impl Strategy<Value = Operation> {
+        prop_oneof![
+            validator_set(NUM_VALIDATORS).prop_map(Operation::Rebalance),
+            Just(Operation::ContinueMigration),
+            validator_set(3).prop_map(Operation::InstantUnstake),
+            (0..NUM_VALIDATORS, 0u64..1_000_000, 0u64..1_000_000).prop_map(
+                |(validator, deposit, withdraw)| Operation::StakeFlow {
+                    validator,
+                    deposit,
+                    withdraw,
+                }
+            ),
+            (0..NUM_VALIDATORS, 0u64..10_000_000).prop_map(|(validator, lamports)| {
+                Operation::Reward {
+                    validator,
+                    lamports,
+                }
+            }),
+            Just(Operation::EpochTransition),
+        ]
+    }
+
+    fn apply_operation(simulator: &mut RebalancingSimulator, operation: Operation) {
+        match operation {
+            Operation::Rebalance(validators) => {
+                select_validators(simulator, &validators);
+                simulator.rebalance_stakes();
+            }
+            Operation::ContinueMigration => {
+                if !simulator.top_validators.is_empty() {
+                    simulator.check_previous_cycle_stake();
+                }
+            }
+            Operation::InstantUnstake(validators) => {
+                let validators: Vec<String> = validators.into_iter().map(vote_account).collect();
+                simulator.handle_instant_unstaking(&validators).unwrap();
+            }
+            Operation::StakeFlow {
+                validator,
+                deposit,
+                withdraw,
+            } => {
+                simulator.epoch_map.insert(
+                    u64::from(simulator.current_epoch),
+                    vec![EpochWithdrawDepositStakeData {
+                        vote_pubkey: vote_account(validator),
+                        withdraw_lamports: withdraw,
+                        deposit_lamports: deposit,
+                        active_balance_lamports: 10_000_000,
+                    }],
+                );
+                simulator
+                    .apply_epoch_stake_changes(simulator.current_epoch)
+                    .unwrap();
+            }
+            Operation::Reward {
+                validator,
+                lamports,
+            } => simulator.apply_reward(&vote_account(validator), lamports, lamports / 2),
+            Operation::EpochTransition => {
+                simulator.current_epoch += 1;
+                simulator.transition_validator_stake_stake().unwrap();
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_lamports_are_conserved(
+            seed_lamports in 1_000u64..1_000_000_000_000,
+            unattributed_stake_flow in prop_oneof![
+                Just(UnattributedStakeFlowPolicy::ProRata),
+                Just(UnattributedStakeFlowPolicy::Reserve),
+                Just(UnattributedStakeFlowPolicy::Random),
+            ],
+            reward_eligibility in prop_oneof![
+                Just(RewardEligibility::ActiveOnly),
+                Just(RewardEligibility::Effective),
+            ],
+            operations in prop::collection::vec(operation(), 1..60),
+        ) {
+            let mut simulator =
+                test_simulator(seed_lamports, unattributed_stake_flow, reward_eligibility);
+
+            for operation in operations {
+                apply_operation(&mut simulator, operation.clone());
+
+                let deactivating: u64 = simulator
+                    .validator_stake_states
+                    .values()
+                    .map(|state| state.deactivating)
+                    .sum();
+                prop_assert!(
+                    simulator.redelegated_lamports <= deactivating,
+                    "redelegated more than was deactivated after {:?}",
+                    operation
+                );
+                prop_assert!(
+                    simulator
+                        .ledger
+                        .check_conservation(simulator.current_epoch, simulator.total_pool_lamports())
+                        .is_ok(),
+                    "ledger expects {} lamports but the pool holds {} after {:?}",
+                    simulator.ledger.expected_pool_lamports(),
+                    simulator.total_pool_lamports(),
+                    operation
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_build_epoch_map_uses_exact_lamports() {
+        let sol = |amount: &str| amount.parse::<BigDecimal>().unwrap();
+        let flows = || {
+            vec![WithdrawsAndDeposits {
+                id: "10-validator-0".to_string(),
+                epoch: 10,
+                vote_pubkey: vote_account(0),
+                withdraw_stake: sol("0.000000001"),
+                deposit_stake: sol("12345678.123456789"),
+            }]
+        };
+        let active_stake = vec![ActiveStakeJitoSol {
+            epoch: 10,
+            balance: sol("14000000.5"),
+        }];
+
+        let epoch_map = RebalancingSimulator::build_epoch_map(flows(), active_stake).unwrap();
+        let data = &epoch_map[&10][0];
+        assert_eq!(data.withdraw_lamports, 1);
+        assert_eq!(data.deposit_lamports, 12_345_678_123_456_789);
+        assert_eq!(data.active_balance_lamports, 14_000_000_500_000_000);
+        assert_eq!(
+            data.scaled_deposit(data.active_balance_lamports),
+            data.deposit_lamports
+        );
+        assert_eq!(data.scaled_withdrawal(1_000), 0);
+
+        let negative_stake = vec![ActiveStakeJitoSol {
+            epoch: 10,
+            balance: sol("-1"),
+        }];
+        assert!(RebalancingSimulator::build_epoch_map(flows(), negative_stake).is_err());
+    }
+
+    #[test]
+    fn test_undelegated_instant_unstake_returns_to_reserve() {
+        let mut simulator = test_simulator(
+            1_000,
+            UnattributedStakeFlowPolicy::ProRata,
+            RewardEligibility::ActiveOnly,
+        );
+        select_validators(&mut simulator, &[0, 1, 2]);
+        simulator.rebalance_stakes();
+        simulator.current_epoch += 1;
+        simulator.transition_validator_stake_stake().unwrap();
+        // 1_000 lamports split across 3 validators leaves 1 in the reserve
+        assert_eq!(simulator.reserve_lamports, 1);
+
+        simulator.instant_unstake_cap_bps = 10_000;
+        simulator
+            .handle_instant_unstaking(&[vote_account(2)])
+            .unwrap();
+        // 333 lamports split across the 2 remaining validators leaves 1 undelegated
+        assert_eq!(simulator.redelegated_lamports, 332);
+        assert_eq!(simulator.total_pool_lamports(), 1_000);
+
+        simulator.current_epoch += 1;
+        simulator.transition_validator_stake_stake().unwrap();
+        assert_eq!(simulator.reserve_lamports, 2);
+        assert_eq!(simulator.total_pool_lamports(), 1_000);
+        assert!(simulator.ledger.check_conservation(2, 1_000).is_ok());
+    }
+
+    #[test]
+    fn test_pool_tvl_without_validator_list_dilutes_the_fixed_rewards() {
+        let mut simulator = test_simulator(
+            1_000,
+            UnattributedStakeFlowPolicy::ProRata,
+            RewardEligibility::ActiveOnly,
+        );
+        simulator.options.simulate_pool_tvl = true;
+        simulator.actual_pool_stakes =
+            HashMap::from([(5, HashMap::from([(vote_account(0), 500)]))]);
+        let reward = EpochRewards {
+            total_mev_rewards: 300,
+            ..test_epoch_rewards(&vote_account(0), 5, 1_000)
+        };
+
+        // The recorded delegation is swapped for the simulated stake
+        assert_eq!(simulator.stake_after_epoch(&reward, 500, 5), 650);
+        // Without it the simulated stake is added on top of the recorded stake
+        assert_eq!(simulator.stake_after_epoch(&reward, 500, 6), 600);
+        assert_eq!(simulator.epochs_without_validator_list(), 99);
+    }
+
+    #[test]
+    fn test_effective_stake_earns_while_deactivating() {
+        let mut simulator = test_simulator(
+            1_000,
+            UnattributedStakeFlowPolicy::ProRata,
+            RewardEligibility::Effective,
+        );
+        select_validators(&mut simulator, &[0, 1, 2]);
+        simulator.rebalance_stakes();
+        simulator.current_epoch += 1;
+        simulator.transition_validator_stake_stake().unwrap();
+
+        simulator.instant_unstake_cap_bps = 10_000;
+        simulator
+            .handle_instant_unstaking(&[vote_account(2)])
+            .unwrap();
+        // The unstaked lamports can't be delegated before they finish deactivating
+        let redelegated = &simulator.validator_stake_states[&vote_account(0)];
+        assert_eq!(redelegated.activating, 0);
+        assert_eq!(redelegated.pending_activation, 166);
+
+        // Rewards of the deactivating stake return to the reserve with it
+        simulator.apply_reward(&vote_account(2), 0, 10);
+        simulator.current_epoch += 1;
+        simulator.transition_validator_stake_stake().unwrap();
+        assert_eq!(simulator.reserve_lamports, 12);
+        let redelegated = &simulator.validator_stake_states[&vote_account(0)];
+        assert_eq!(redelegated.active, 333);
+        assert_eq!(redelegated.activating, 166);
+
+        simulator.current_epoch += 1;
+        simulator.transition_validator_stake_stake().unwrap();
+        assert_eq!(
+            simulator.validator_stake_states[&vote_account(0)].active,
+            499
+        );
+        assert!(simulator.ledger.check_conservation(3, 1_010).is_ok());
+    }
+
+    #[test]
+    fn test_deposits_leave_the_net_apy_unchanged() {
+        let net_apy = |deposit: u64| {
+            let mut simulator = funded_simulator(
+                StakeFlowAttribution::Actual,
+                UnattributedStakeFlowPolicy::ProRata,
+                &[(0, 2_000_000)],
+                500_000,
+            );
+            record_stake_flow(&mut simulator, 0, deposit, 0);
+            simulator.apply_epoch_stake_changes(0).unwrap();
+            simulator.complete_cycle(2_500_000);
+            (
+                calculate_aggregated_apy(&simulator.rebalancing_cycles, 10).unwrap(),
+                calculate_net_aggregated_apy(&simulator.rebalancing_cycles, 10).unwrap(),
+            )
+        };
+
+        let (_, net_without_deposit) = net_apy(0);
+        let (gross_with_deposit, net_with_deposit) = net_apy(1_000_000);
+        assert_eq!(net_without_deposit, 0.0);
+        // The deposit grows the pool's lamports, but not the exchange rate
+        assert!(gross_with_deposit > 0.0);
+        assert!(net_with_deposit.abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_withdrawal_fees_are_charged_on_applied_stake() {
+        for (attribution, policy) in [
+            (
+                StakeFlowAttribution::Random,
+                UnattributedStakeFlowPolicy::ProRata,
+            ),
+            (
+                StakeFlowAttribution::Actual,
+                UnattributedStakeFlowPolicy::ProRata,
+            ),
+            (
+                StakeFlowAttribution::Actual,
+                UnattributedStakeFlowPolicy::Random,
+            ),
+        ] {
+            let mut simulator = funded_simulator(attribution, policy, &[(0, 2_000_000)], 500_000);
+            // Withdrawing more than the validator holds only takes its 2_000_000 lamports
+            record_stake_flow(&mut simulator, 0, 0, 3_125_000);
+            simulator.apply_epoch_stake_changes(0).unwrap();
+
+            assert_eq!(
+                simulator.validator_stake_states[&vote_account(0)].active,
+                0,
+                "{attribution:?} {policy:?}"
+            );
+            assert_eq!(simulator.reserve_lamports, 500_000);
+            assert_eq!(
+                simulator.current_epoch_fees.withdrawal_fee, 20_000,
+                "{attribution:?} {policy:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_unattributed_deposits_follow_the_policy() {
+        let deposit = |policy| {
+            let mut simulator = funded_simulator(
+                StakeFlowAttribution::Actual,
+                policy,
+                &[(0, 1_000_000), (1, 1_000_000)],
+                500_000,
+            );
+            // Validator 5 isn't in the simulated set
+            record_stake_flow(&mut simulator, 5, 500_000, 0);
+            simulator.apply_epoch_stake_changes(0).unwrap();
+            simulator
+        };
+        let active = |simulator: &RebalancingSimulator, index| {
+            simulator.validator_stake_states[&vote_account(index)].active
+        };
+
+        let pro_rata = deposit(UnattributedStakeFlowPolicy::ProRata);
+        assert_eq!(active(&pro_rata, 0), 1_250_000);
+        assert_eq!(active(&pro_rata, 1), 1_250_000);
+        assert_eq!(active(&pro_rata, 5), 0);
+        assert_eq!(pro_rata.reserve_lamports, 500_000);
+        assert_eq!(pro_rata.current_epoch_fees.deposit_fee, 5_000);
+
+        // Deposits absorbed by the reserve are charged the SOL deposit fee
+        let reserve = deposit(UnattributedStakeFlowPolicy::Reserve);
+        assert_eq!(active(&reserve, 0), 1_000_000);
+        assert_eq!(active(&reserve, 1), 1_000_000);
+        assert_eq!(reserve.reserve_lamports, 1_000_000);
+        assert_eq!(reserve.current_epoch_fees.deposit_fee, 10_000);
+
+        let random = deposit(UnattributedStakeFlowPolicy::Random);
+        let mut actives = [active(&random, 0), active(&random, 1)];
+        actives.sort_unstable();
+        assert_eq!(actives, [1_000_000, 1_500_000]);
+        assert_eq!(random.reserve_lamports, 500_000);
+        assert_eq!(random.current_epoch_fees.deposit_fee, 5_000);
+    }
+
+    #[test]
+    fn test_skip_rates_prefer_the_fetched_block_production() {
+        let mut simulator = test_simulator(
+            1_000,
+            UnattributedStakeFlowPolicy::ProRata,
+            RewardEligibility::ActiveOnly,
+        );
+        simulator.steward_config.parameters.epoch_credits_range = 5;
+        let entry = |epoch: u16| ValidatorHistoryEntry {
+            id: format!("{epoch}-{}", vote_account(0)),
+            vote_pubkey: vote_account(0),
+            validator_history_entry: JitoValidatorHistoryEntry {
+                epoch,
+                total_leader_slots: 100,
+                blocks_produced: 50,
+                ..Default::default()
+            },
+        };
+        simulator.entries_by_validator = Arc::new(HashMap::from([(
+            vote_account(0),
+            vec![entry(8), entry(9), entry(10)],
+        )]));
+        simulator.block_production =
+            RebalancingSimulator::build_block_production(vec![VoteAccountBlockProduction {
+                vote_pubkey: vote_account(0),
+                epoch: 9,
+                leader_slots: 100,
+                skipped_slots: 10,
+                rpc_missing_slots: 0,
+            }]);
+
+        // Epoch 8 from the history entry, epoch 9 from the block production, epoch 10 isn't
+        // completed yet
+        let skip_rates = simulator.skip_rates_at(10);
+        assert!((skip_rates[&vote_account(0)] - 0.3).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_scenario_applies_to_synthetic_epochs() {
+        let mut simulator = test_simulator(
+            1_000,
+            UnattributedStakeFlowPolicy::ProRata,
+            RewardEligibility::ActiveOnly,
+        );
+        simulator.options.scenario = Scenario {
+            events: vec![ScenarioEvent {
+                kind: ScenarioEventKind::CommissionRug {
+                    vote_account: vote_account(0),
+                },
+                start_epoch: 11,
+                epochs: None,
+            }],
+        };
+        let entry = |index: usize, epoch: u16| ValidatorHistoryEntry {
+            id: format!("{epoch}-{}", vote_account(index)),
+            vote_pubkey: vote_account(index),
+            validator_history_entry: JitoValidatorHistoryEntry {
+                epoch,
+                commission: 5,
+                ..Default::default()
+            },
+        };
+        simulator.entries_by_validator = Arc::new(HashMap::from([(
+            vote_account(0),
+            vec![entry(0, 9), entry(0, 10)],
+        )]));
+
+        simulator.add_synthetic_epochs(SyntheticEpochs {
+            first_epoch: 10,
+            entries: vec![entry(0, 10), entry(0, 11), entry(1, 11)],
+            ..Default::default()
+        });
+
+        let commissions = |index: usize| -> Vec<(u16, u8)> {
+            simulator.entries_by_validator[&vote_account(index)]
+                .iter()
+                .map(|entry| {
+                    (
+                        entry.validator_history_entry.epoch,
+                        entry.validator_history_entry.commission,
+                    )
+                })
+                .collect()
+        };
+        assert_eq!(commissions(0), vec![(9, 5), (10, 5), (11, 100)]);
+        assert_eq!(commissions(1), vec![(11, 5)]);
+    }
+
+    #[test]
+    fn test_point_in_time_entries_only_use_finalized_data() {
+        let mut simulator = test_simulator(
+            1_000,
+            UnattributedStakeFlowPolicy::ProRata,
+            RewardEligibility::ActiveOnly,
+        );
+        simulator.options.point_in_time = true;
+        let entry = |epoch: u16, epoch_credits: u32, blocks_produced: u32| ValidatorHistoryEntry {
+            id: format!("{epoch}-{}", vote_account(0)),
+            vote_pubkey: vote_account(0),
+            validator_history_entry: JitoValidatorHistoryEntry {
+                epoch,
+                epoch_credits,
+                blocks_produced,
+                mev_earned: 7,
+                ..Default::default()
+            },
+        };
+        simulator.entries_by_validator = Arc::new(HashMap::from([(
+            vote_account(0),
+            vec![entry(9, 1_000, 50), entry(10, 300, 20), entry(11, 900, 40)],
+        )]));
+        simulator.cluster_history_entries = [(9, 400_000), (10, 100_000)]
+            .into_iter()
+            .map(|(epoch, total_blocks)| ClusterHistoryEntry {
+                epoch,
+                total_blocks,
+                epoch_start_timestamp: 0,
+            })
+            .collect();
+
+        let entries = simulator.get_current_epoch_entries(10);
+        let entries = &entries[&vote_account(0)];
+        assert_eq!(entries.len(), 2);
+        let current = &entries[0].validator_history_entry;
+        let unset = JitoValidatorHistoryEntry::default();
+        assert_eq!(current.epoch, 10);
+        assert_eq!(current.epoch_credits, 1_000);
+        assert_eq!(current.blocks_produced, unset.blocks_produced);
+        assert_eq!(current.mev_earned, unset.mev_earned);
+        let previous = &entries[1].validator_history_entry;
+        assert_eq!(
+            (previous.epoch_credits, previous.blocks_produced),
+            (1_000, 50)
+        );
+
+        simulator.cache_point_in_time_cluster_history(10);
+        let cluster_history = simulator.cluster_history_at(10);
+        let latest = cluster_history.history.last().unwrap();
+        assert_eq!((latest.epoch, latest.total_blocks), (10, 400_000));
+        assert!(Arc::ptr_eq(
+            &cluster_history,
+            &simulator.cluster_history_at(10)
+        ));
+
+        simulator.options.point_in_time = false;
+        let entries = simulator.get_current_epoch_entries(10);
+        assert_eq!(
+            entries[&vote_account(0)][0]
+                .validator_history_entry
+                .epoch_credits,
+            300
+        );
+    }
+
+    #[test]
+    fn test_parameter_schedule_drives_the_steward_cycles() {
+        let mut simulator = test_simulator(
+            1_000,
+            UnattributedStakeFlowPolicy::ProRata,
+            RewardEligibility::ActiveOnly,
+        );
+        let mut parameters = Config::zeroed().parameters;
+        parameters.num_delegation_validators = 100;
+        parameters.num_epochs_between_scoring = 5;
+        let initial = StewardConfigHistory::from_parameters(0, &parameters);
+        parameters.num_delegation_validators = 50;
+        parameters.num_epochs_between_scoring = 3;
+        parameters.instant_unstake_cap_bps = 200;
+        let updated = StewardConfigHistory::from_parameters(20, &parameters);
+        simulator.options.parameter_schedule = vec![initial, updated];
+
+        let mut rebalancing_epochs = Vec::new();
+        for epoch in 0..30 {
+            simulator.apply_parameter_schedule(epoch);
+            if epoch == 19 {
+                assert_eq!(simulator.number_of_validator_delegations, 100);
+                assert_eq!(simulator.steward_cycle_rate, 5);
+            }
+            if simulator.is_rebalancing_epoch(epoch) {
+                rebalancing_epochs.push(epoch);
+                simulator.current_cycle_end = epoch + simulator.steward_cycle_rate;
+            }
+        }
+
+        assert_eq!(simulator.number_of_validator_delegations, 50);
+        assert_eq!(simulator.steward_cycle_rate, 3);
+        assert_eq!(simulator.instant_unstake_cap_bps, 200);
+        assert_eq!(rebalancing_epochs, vec![0, 5, 10, 15, 20, 23, 26, 29]);
+    }
+
+    #[test]
+    fn test_parameter_schedule_must_cover_the_simulation_start() {
+        let mut simulator = test_simulator(
+            1_000,
+            UnattributedStakeFlowPolicy::ProRata,
+            RewardEligibility::ActiveOnly,
+        );
+        simulator.simulation_start_epoch = 10;
+        assert!(simulator.check_parameter_schedule().is_ok());
+
+        let parameters = Config::zeroed().parameters;
+        simulator.options.parameter_schedule = vec![
+            StewardConfigHistory::from_parameters(12, &parameters),
+            StewardConfigHistory::from_parameters(20, &parameters),
+        ];
+        assert!(matches!(
+            simulator.check_parameter_schedule(),
+            Err(CliError::ParameterScheduleNotFound(10))
+        ));
+
+        simulator
+            .options
+            .parameter_schedule
+            .insert(0, StewardConfigHistory::from_parameters(10, &parameters));
+        assert!(simulator.check_parameter_schedule().is_ok());
+    }
+
+    #[test]
+    fn test_unapplied_withdrawals_are_not_rounding() {
+        let mut simulator = funded_simulator(
+            StakeFlowAttribution::Actual,
+            UnattributedStakeFlowPolicy::ProRata,
+            &[(0, 1_000_000), (1, 1_000_001), (2, 999_999)],
+            0,
+        );
+        // Validator 5 isn't in the simulated set, so the withdrawal is spread pro rata
+        record_stake_flow(&mut simulator, 5, 0, 1_000_000);
+        simulator.apply_epoch_stake_changes(0).unwrap();
+        assert_eq!(simulator.total_pool_lamports(), 2_000_000);
+        assert_eq!(
+            simulator
+                .ledger
+                .epoch_total(0, LamportMovementKind::UnappliedFlow),
+            0
+        );
+        assert_eq!(
+            simulator
+                .ledger
+                .epoch_total(0, LamportMovementKind::Rounding),
+            0
+        );
+
+        // Withdrawing more than the pool holds leaves the rest unapplied
+        simulator.current_epoch = 1;
+        record_stake_flow(&mut simulator, 5, 0, 5_000_000);
+        simulator.apply_epoch_stake_changes(1).unwrap();
+        assert_eq!(simulator.total_pool_lamports(), 0);
+        assert_eq!(
+            simulator
+                .ledger
+                .epoch_total(1, LamportMovementKind::Withdrawal),
+            -5_000_000
+        );
+        let unapplied = simulator
+            .ledger
+            .epoch_total(1, LamportMovementKind::UnappliedFlow);
+        let rounding = simulator
+            .ledger
+            .epoch_total(1, LamportMovementKind::Rounding);
+        assert_eq!(unapplied + rounding, 3_000_000);
+        assert!(simulator.ledger.check_rounding(1, 3).is_ok());
+        assert!(simulator.ledger.check_conservation(1, 0).is_ok());
+    }
+
+    #[test]
+    fn test_forgone_rewards_can_exceed_the_validator_stake() {
+        let reward = EpochRewards {
+            inflation_commission_bps: 5_000,
+            total_inflation_rewards: 200,
+            ..test_epoch_rewards(&vote_account(0), 0, 1_000)
+        };
+        assert_eq!(rewards_at_validator_rate(&reward, 500), 50);
+        // More stake in transition than the validator's recorded active stake
+        assert_eq!(rewards_at_validator_rate(&reward, 5_000), 500);
+
+        let no_stake = EpochRewards {
+            active_stake: 0,
+            ..reward
+        };
+        assert_eq!(rewards_at_validator_rate(&no_stake, 5_000), 0);
+    }
+
+    #[test]
+    fn test_equal_weight_counts_deactivating_stake() {
+        let mut simulator = funded_simulator(
+            StakeFlowAttribution::Actual,
+            UnattributedStakeFlowPolicy::ProRata,
+            &[(0, 500_000), (1, 300_000)],
+            200_000,
+        );
+        simulator
+            .validator_stake_states
+            .get_mut(&vote_account(0))
+            .unwrap()
+            .deactivate(200_000);
+
+        simulator.redistribute_stakes(1_000_000, true);
+        // Validator 0 is still at its target until its stake finishes deactivating
+        assert_eq!(
+            simulator.validator_stake_states[&vote_account(0)].activating,
+            0
+        );
+        assert_eq!(
+            simulator.validator_stake_states[&vote_account(1)].activating,
+            200_000
+        );
+        assert_eq!(simulator.reserve_lamports, 0);
+    }
+
+    #[test]
+    fn test_deactivate_above_targets_respects_the_turnover_budget() {
+        let mut simulator = funded_simulator(
+            StakeFlowAttribution::Actual,
+            UnattributedStakeFlowPolicy::ProRata,
+            &[(0, 600_000), (1, 400_000)],
+            0,
+        );
+        simulator.options.allocation_mode = AllocationMode::ScoreProportional;
+        simulator.options.max_turnover_bps = Some(1_000);
+        for (index, target) in [(0, 300_000), (1, 700_000)] {
+            simulator
+                .validator_stake_states
+                .get_mut(&vote_account(index))
+                .unwrap()
+                .target = target;
+        }
+        let validators = simulator.top_validators.clone();
+
+        // 10% of the pool can be moved this cycle
+        simulator.deactivate_above_targets(&validators);
+        let state = &simulator.validator_stake_states[&vote_account(0)];
+        assert_eq!((state.active, state.deactivating), (500_000, 100_000));
+        assert_eq!(simulator.pending_deactivation, 100_000);
+        assert_eq!(
+            simulator
+                .current_cycle_turnover
+                .rebalance_deactivated_lamports,
+            100_000
+        );
+
+        // The budget is used up for the rest of the cycle
+        simulator.deactivate_above_targets(&validators);
+        assert_eq!(
+            simulator.validator_stake_states[&vote_account(0)].deactivating,
+            100_000
+        );
+        assert_eq!(simulator.pending_deactivation, 100_000);
+    }
+}
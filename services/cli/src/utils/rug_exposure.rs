@@ -0,0 +1,107 @@
+use validator_history::ValidatorHistoryEntry as JitoValidatorHistoryEntry;
+
+/// Pool stake delegated to a validator when its commission jumped above the steward thresholds
+#[derive(Clone, Debug, Default)]
+pub struct RugExposure {
+    pub vote_account: String,
+    /// Epoch the raised commission took effect
+    pub epoch: u16,
+    pub commission_before: u8,
+    pub commission_after: u8,
+    pub mev_commission_bps_before: u16,
+    pub mev_commission_bps_after: u16,
+    /// Simulated pool stake delegated to the validator at the rug epoch
+    pub delegated_lamports: u64,
+    /// Epoch the last of the pool's stake was taken off the validator
+    pub removed_epoch: Option<u16>,
+    /// Whether the steward instantly unstaked the validator, as opposed to it leaving the set
+    /// at a scoring change
+    pub instantly_unstaked: bool,
+    /// Rewards the pool would have earned had the validator kept its commission
+    pub rewards_lost_lamports: u64,
+}
+
+impl RugExposure {
+    pub fn is_open(&self) -> bool {
+        self.removed_epoch.is_none()
+    }
+
+    /// Epochs between the rug and the pool's stake being taken off the validator
+    pub fn epochs_to_removal(&self) -> Option<u16> {
+        self.removed_epoch.map(|epoch| epoch - self.epoch)
+    }
+
+    /// Commissions the pool's rewards would have been charged at without the rug, in bps
+    pub fn commission_bps_before(&self) -> (u16, u16) {
+        (
+            u16::from(self.commission_before) * 100,
+            self.mev_commission_bps_before,
+        )
+    }
+}
+
+/// Whether the validator's commission or MEV commission crossed above the steward threshold
+/// between two consecutive entries. Unset commissions are ignored.
+pub fn is_commission_rug(
+    previous: &JitoValidatorHistoryEntry,
+    current: &JitoValidatorHistoryEntry,
+    commission_threshold: u8,
+    mev_commission_bps_threshold: u16,
+) -> bool {
+    let commission_rug = previous.commission != u8::MAX
+        && current.commission != u8::MAX
+        && previous.commission <= commission_threshold
+        && current.commission > commission_threshold;
+    let mev_commission_rug = previous.mev_commission != u16::MAX
+        && current.mev_commission != u16::MAX
+        && previous.mev_commission <= mev_commission_bps_threshold
+        && current.mev_commission > mev_commission_bps_threshold;
+    commission_rug || mev_commission_rug
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(commission: u8, mev_commission: u16) -> JitoValidatorHistoryEntry {
+        JitoValidatorHistoryEntry {
+            commission,
+            mev_commission,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_commission_rug_crosses_the_threshold() {
+        assert!(is_commission_rug(
+            &entry(5, 800),
+            &entry(100, 800),
+            5,
+            1_000
+        ));
+        assert!(is_commission_rug(
+            &entry(5, 800),
+            &entry(5, 10_000),
+            5,
+            1_000
+        ));
+        assert!(!is_commission_rug(
+            &entry(10, 800),
+            &entry(100, 800),
+            5,
+            1_000
+        ));
+        assert!(!is_commission_rug(
+            &entry(5, u16::MAX),
+            &entry(5, 800),
+            5,
+            1_000
+        ));
+        assert!(!is_commission_rug(
+            &entry(u8::MAX, 800),
+            &entry(100, 800),
+            5,
+            1_000
+        ));
+    }
+}
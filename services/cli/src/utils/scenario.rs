@@ -0,0 +1,253 @@
+use crate::error::CliError;
+use solana_sdk::pubkey::Pubkey;
+use stakenet_simulator_db::{
+    epoch_rewards::EpochRewards, validator_history_entry::ValidatorHistoryEntry,
+};
+use std::{collections::HashMap, fs, path::Path, str::FromStr};
+
+const MAX_BPS: u64 = 10_000;
+
+/// A synthetic event injected into the historical data
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ScenarioEventKind {
+    /// The validator raises its inflation commission to 100%
+    CommissionRug { vote_account: String },
+    /// Every validator running the client type stops voting and producing blocks
+    ClientDelinquent { client_type: u8 },
+    /// MEV rewards of every validator drop by `reduction_bps`
+    MevReduction { reduction_bps: u16 },
+    /// The validator earns no epoch credits, and so no inflation rewards
+    ZeroCredits { vote_account: String },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScenarioEvent {
+    pub kind: ScenarioEventKind,
+    pub start_epoch: u16,
+    /// Number of epochs the event lasts, until the end of the simulation when unset
+    pub epochs: Option<u16>,
+}
+
+impl ScenarioEvent {
+    pub fn is_active(&self, epoch: u16) -> bool {
+        epoch >= self.start_epoch
+            && self.epochs.is_none_or(|epochs| {
+                u32::from(epoch) < u32::from(self.start_epoch) + u32::from(epochs)
+            })
+    }
+}
+
+/// Scripted modifications applied to the validator history and epoch rewards before they are
+/// used by the simulation
+#[derive(Clone, Debug, Default)]
+pub struct Scenario {
+    pub events: Vec<ScenarioEvent>,
+}
+
+impl Scenario {
+    /// Reads a scenario file. Every non-empty line holds an event, the value it applies to, the
+    /// epoch it starts at and optionally the number of epochs it lasts:
+    ///
+    /// ```text
+    /// commission-rug <vote_account> <epoch> [<epochs>]
+    /// client-delinquent <client_type> <epoch> [<epochs>]
+    /// mev-reduction <percent> <epoch> [<epochs>]
+    /// zero-credits <vote_account> <epoch> [<epochs>]
+    /// ```
+    ///
+    /// Lines starting with `#` are ignored.
+    pub fn read(path: &Path) -> Result<Self, CliError> {
+        let contents = fs::read_to_string(path)?;
+        Self::parse(&contents)
+    }
+
+    fn parse(contents: &str) -> Result<Self, CliError> {
+        let events = contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                let invalid = || CliError::InvalidScenario(line.to_string());
+                let parts: Vec<&str> = line.split_whitespace().collect();
+                let [event, value, start_epoch, rest @ ..] = parts.as_slice() else {
+                    return Err(invalid());
+                };
+                let epochs = match rest {
+                    [] => None,
+                    [epochs] => Some(epochs.parse::<u16>().map_err(|_| invalid())?),
+                    _ => return Err(invalid()),
+                };
+                let vote_account = || {
+                    Pubkey::from_str(value)
+                        .map(|_| value.to_string())
+                        .map_err(|_| invalid())
+                };
+
+                let kind = match *event {
+                    "commission-rug" => ScenarioEventKind::CommissionRug {
+                        vote_account: vote_account()?,
+                    },
+                    "client-delinquent" => ScenarioEventKind::ClientDelinquent {
+                        client_type: value.parse().map_err(|_| invalid())?,
+                    },
+                    "mev-reduction" => {
+                        let percent: u16 = value.parse().map_err(|_| invalid())?;
+                        if percent > 100 {
+                            return Err(invalid());
+                        }
+                        ScenarioEventKind::MevReduction {
+                            reduction_bps: percent * 100,
+                        }
+                    }
+                    "zero-credits" => ScenarioEventKind::ZeroCredits {
+                        vote_account: vote_account()?,
+                    },
+                    _ => return Err(invalid()),
+                };
+
+                Ok(ScenarioEvent {
+                    kind,
+                    start_epoch: start_epoch.parse().map_err(|_| invalid())?,
+                    epochs,
+                })
+            })
+            .collect::<Result<Vec<_>, CliError>>()?;
+
+        Ok(Self { events })
+    }
+
+    /// Applies the events to every validator history entry of the epochs they are active in
+    pub fn apply_to_entries(
+        &self,
+        entries_by_validator: &mut HashMap<String, Vec<ValidatorHistoryEntry>>,
+    ) {
+        for (vote_account, entries) in entries_by_validator.iter_mut() {
+            for entry in entries.iter_mut() {
+                let entry = &mut entry.validator_history_entry;
+                for event in self
+                    .events
+                    .iter()
+                    .filter(|event| event.is_active(entry.epoch))
+                {
+                    match &event.kind {
+                        ScenarioEventKind::CommissionRug {
+                            vote_account: rugging,
+                        } if rugging == vote_account => {
+                            entry.commission = 100;
+                        }
+                        ScenarioEventKind::ClientDelinquent { client_type }
+                            if *client_type == entry.client_type =>
+                        {
+                            entry.epoch_credits = 0;
+                            entry.blocks_produced = 0;
+                            entry.mev_earned = 0;
+                        }
+                        ScenarioEventKind::MevReduction { reduction_bps } => {
+                            entry.mev_earned = (u64::from(entry.mev_earned)
+                                * (MAX_BPS - u64::from(*reduction_bps))
+                                / MAX_BPS) as u32;
+                        }
+                        ScenarioEventKind::ZeroCredits {
+                            vote_account: zeroed,
+                        } if zeroed == vote_account => {
+                            entry.epoch_credits = 0;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    /// Applies the events active at `epoch` to the epoch's rewards. `client_types` maps vote
+    /// accounts to the client type they ran at the epoch.
+    pub fn apply_to_rewards(
+        &self,
+        epoch: u16,
+        rewards: &mut [EpochRewards],
+        client_types: &HashMap<String, u8>,
+    ) {
+        for event in self.events.iter().filter(|event| event.is_active(epoch)) {
+            for reward in rewards.iter_mut() {
+                match &event.kind {
+                    ScenarioEventKind::CommissionRug { vote_account }
+                        if *vote_account == reward.vote_pubkey =>
+                    {
+                        reward.inflation_commission_bps = MAX_BPS as u16;
+                    }
+                    ScenarioEventKind::ClientDelinquent { client_type }
+                        if client_types.get(&reward.vote_pubkey) == Some(client_type) =>
+                    {
+                        reward.total_inflation_rewards = 0;
+                        reward.total_mev_rewards = 0;
+                        reward.total_priority_fee_rewards = 0;
+                    }
+                    ScenarioEventKind::MevReduction { reduction_bps } => {
+                        reward.total_mev_rewards = (u128::from(reward.total_mev_rewards)
+                            * u128::from(MAX_BPS - u64::from(*reduction_bps))
+                            / u128::from(MAX_BPS))
+                            as u64;
+                    }
+                    ScenarioEventKind::ZeroCredits { vote_account }
+                        if *vote_account == reward.vote_pubkey =>
+                    {
+                        reward.total_inflation_rewards = 0;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use validator_history::ValidatorHistoryEntry as JitoValidatorHistoryEntry;
+
+    const VOTE_ACCOUNT: &str = "J1to1yufRnoWn81KYg1XkTWzmKjnYSnmE2VY8DGUJ9Qv";
+
+    #[test]
+    fn test_scenario_modifies_entries_while_active() {
+        let scenario = Scenario::parse(&format!(
+            "# stress test\ncommission-rug {VOTE_ACCOUNT} 801\nmev-reduction 50 800 1\n"
+        ))
+        .unwrap();
+        assert_eq!(scenario.events.len(), 2);
+
+        let mut entries_by_validator = HashMap::from([(
+            VOTE_ACCOUNT.to_string(),
+            (800..803)
+                .map(|epoch| {
+                    ValidatorHistoryEntry::new(
+                        VOTE_ACCOUNT.to_string(),
+                        JitoValidatorHistoryEntry {
+                            epoch,
+                            commission: 5,
+                            mev_earned: 1_000,
+                            ..Default::default()
+                        },
+                    )
+                })
+                .collect(),
+        )]);
+        scenario.apply_to_entries(&mut entries_by_validator);
+
+        let entries: Vec<(u8, u32)> = entries_by_validator[VOTE_ACCOUNT]
+            .iter()
+            .map(|entry| {
+                (
+                    entry.validator_history_entry.commission,
+                    entry.validator_history_entry.mev_earned,
+                )
+            })
+            .collect();
+        assert_eq!(entries, vec![(5, 500), (100, 1_000), (100, 1_000)]);
+    }
+
+    #[test]
+    fn test_invalid_scenario_line_is_rejected() {
+        assert!(Scenario::parse("mev-reduction 150 800").is_err());
+        assert!(Scenario::parse("commission-rug not-a-pubkey 800").is_err());
+    }
+}
@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+
+/// A validator whose simulated score differs from the one the steward program computed
+#[derive(Clone, Debug)]
+pub struct ScoreDivergence {
+    pub vote_account: String,
+    pub simulated: f64,
+    pub recorded: f64,
+}
+
+impl ScoreDivergence {
+    pub fn abs_difference(&self) -> f64 {
+        (self.simulated - self.recorded).abs()
+    }
+}
+
+/// Comparison of simulated scores with the scores recorded from the steward state account
+#[derive(Clone, Debug, Default)]
+pub struct ScoreDiff {
+    /// Validators with both a simulated and a recorded score
+    pub compared: usize,
+    /// Recorded validators the simulation didn't score
+    pub missing_from_simulation: usize,
+    /// Divergences larger than the tolerance, largest first
+    pub divergences: Vec<ScoreDivergence>,
+    pub max_abs_difference: f64,
+    pub mean_abs_difference: f64,
+}
+
+impl ScoreDiff {
+    /// Compares the scores of every validator in `recorded` with its simulated score. Scores
+    /// that differ by no more than `tolerance` are treated as matching.
+    pub fn new(
+        simulated: &HashMap<String, f64>,
+        recorded: &HashMap<String, f64>,
+        tolerance: f64,
+    ) -> Self {
+        let mut diff = Self::default();
+        let mut total_abs_difference = 0.0;
+
+        for (vote_account, recorded_score) in recorded {
+            let Some(simulated_score) = simulated.get(vote_account) else {
+                diff.missing_from_simulation += 1;
+                continue;
+            };
+            let divergence = ScoreDivergence {
+                vote_account: vote_account.clone(),
+                simulated: *simulated_score,
+                recorded: *recorded_score,
+            };
+            let abs_difference = divergence.abs_difference();
+
+            diff.compared += 1;
+            total_abs_difference += abs_difference;
+            diff.max_abs_difference = diff.max_abs_difference.max(abs_difference);
+            if abs_difference > tolerance {
+                diff.divergences.push(divergence);
+            }
+        }
+
+        if diff.compared > 0 {
+            diff.mean_abs_difference = total_abs_difference / diff.compared as f64;
+        }
+        diff.divergences
+            .sort_by(|a, b| b.abs_difference().total_cmp(&a.abs_difference()));
+
+        diff
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score_diff() {
+        let simulated = HashMap::from([
+            ("a".to_string(), 0.9),
+            ("b".to_string(), 0.5),
+            ("c".to_string(), 0.0),
+        ]);
+        let recorded = HashMap::from([
+            ("a".to_string(), 0.9),
+            ("b".to_string(), 0.8),
+            ("c".to_string(), 0.1),
+            ("d".to_string(), 1.0),
+        ]);
+
+        let diff = ScoreDiff::new(&simulated, &recorded, 0.05);
+        assert_eq!(diff.compared, 3);
+        assert_eq!(diff.missing_from_simulation, 1);
+        let divergent: Vec<&str> = diff
+            .divergences
+            .iter()
+            .map(|divergence| divergence.vote_account.as_str())
+            .collect();
+        assert_eq!(divergent, vec!["b", "c"]);
+        assert!((diff.max_abs_difference - 0.3).abs() < 1e-9);
+        assert!((diff.mean_abs_difference - 0.4 / 3.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,147 @@
+use stakenet_simulator_db::epoch_block_production::VoteAccountBlockProduction;
+use validator_history::ValidatorHistoryEntry as JitoValidatorHistoryEntry;
+
+/// Skip rate based scoring applied on top of the steward score
+#[derive(Clone, Debug, Default)]
+pub struct SkipRateScoring {
+    /// Validators skipping a larger share of their leader slots are scored 0
+    pub max_skip_rate: Option<f64>,
+    /// Scores are multiplied by `1 - score_weight * skip_rate`
+    pub score_weight: f64,
+}
+
+impl SkipRateScoring {
+    pub fn is_enabled(&self) -> bool {
+        self.max_skip_rate.is_some() || self.score_weight > 0.0
+    }
+
+    /// The score adjusted for the validator's skip rate. Validators without leader slots
+    /// recorded keep their score.
+    pub fn adjusted_score(&self, score: f64, skip_rate: Option<f64>) -> f64 {
+        let Some(skip_rate) = skip_rate else {
+            return score;
+        };
+        if self
+            .max_skip_rate
+            .is_some_and(|max_skip_rate| skip_rate > max_skip_rate)
+        {
+            return 0.0;
+        }
+        score * (1.0 - self.score_weight * skip_rate).max(0.0)
+    }
+}
+
+/// A validator's leader slots in an epoch and how many of them didn't produce a block
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LeaderSlots {
+    pub leader_slots: u32,
+    pub skipped_slots: u32,
+}
+
+impl LeaderSlots {
+    /// From the block production recorded in the validator history, `None` when it isn't
+    /// recorded
+    pub fn from_history_entry(entry: &JitoValidatorHistoryEntry) -> Option<Self> {
+        let unset = JitoValidatorHistoryEntry::default();
+        if entry.total_leader_slots == unset.total_leader_slots
+            || entry.blocks_produced == unset.blocks_produced
+        {
+            return None;
+        }
+        Some(Self {
+            leader_slots: entry.total_leader_slots,
+            skipped_slots: entry
+                .total_leader_slots
+                .saturating_sub(entry.blocks_produced),
+        })
+    }
+
+    /// From the blocks fetched into `epoch_block_production`, leaving out the slots missing on
+    /// the RPC
+    pub fn from_block_production(block_production: &VoteAccountBlockProduction) -> Self {
+        let leader_slots = block_production.known_slots();
+        Self {
+            leader_slots,
+            skipped_slots: block_production.skipped_slots.min(leader_slots),
+        }
+    }
+}
+
+/// Share of the leader slots that didn't produce a block, `None` when there are no leader
+/// slots at all
+pub fn skip_rate(slots: impl IntoIterator<Item = LeaderSlots>) -> Option<f64> {
+    let (leader_slots, skipped_slots) =
+        slots
+            .into_iter()
+            .fold((0u64, 0u64), |(leader_slots, skipped_slots), slots| {
+                (
+                    leader_slots + u64::from(slots.leader_slots),
+                    skipped_slots + u64::from(slots.skipped_slots),
+                )
+            });
+    if leader_slots == 0 {
+        return None;
+    }
+    Some(skipped_slots as f64 / leader_slots as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(total_leader_slots: u32, blocks_produced: u32) -> JitoValidatorHistoryEntry {
+        JitoValidatorHistoryEntry {
+            total_leader_slots,
+            blocks_produced,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_skip_rate_adjusts_the_score() {
+        let entries = [
+            entry(40, 36),
+            entry(60, 54),
+            JitoValidatorHistoryEntry::default(),
+        ];
+        let rate = skip_rate(entries.iter().filter_map(LeaderSlots::from_history_entry)).unwrap();
+        assert!((rate - 0.1).abs() < 1e-12);
+        assert_eq!(
+            LeaderSlots::from_history_entry(&JitoValidatorHistoryEntry::default()),
+            None
+        );
+        assert_eq!(skip_rate([]), None);
+
+        let scoring = SkipRateScoring {
+            max_skip_rate: Some(0.2),
+            score_weight: 1.0,
+        };
+        assert!((scoring.adjusted_score(0.5, Some(rate)) - 0.45).abs() < 1e-12);
+        assert_eq!(scoring.adjusted_score(0.5, Some(0.25)), 0.0);
+        assert_eq!(scoring.adjusted_score(0.5, None), 0.5);
+    }
+
+    #[test]
+    fn test_block_production_leaves_out_missing_slots() {
+        let block_production = VoteAccountBlockProduction {
+            vote_pubkey: "validator".to_string(),
+            epoch: 800,
+            leader_slots: 100,
+            skipped_slots: 9,
+            rpc_missing_slots: 10,
+        };
+        let slots = LeaderSlots::from_block_production(&block_production);
+        assert_eq!(
+            slots,
+            LeaderSlots {
+                leader_slots: 90,
+                skipped_slots: 9,
+            }
+        );
+        let rate = skip_rate([
+            slots,
+            LeaderSlots::from_history_entry(&entry(10, 10)).unwrap(),
+        ]);
+        assert!((rate.unwrap() - 0.09).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,61 @@
+use std::ops::AddAssign;
+
+/// Stake moved by the steward during a cycle and what moving it cost
+#[derive(Clone, Debug, Default)]
+pub struct CycleTurnover {
+    /// Lamports deactivated from validators that dropped out of the delegation set
+    pub scoring_deactivated_lamports: u64,
+    /// Lamports deactivated from validators that were instantly unstaked
+    pub instant_unstake_deactivated_lamports: u64,
+    /// Lamports deactivated because a validator received more stake deposits than the steward
+    /// delegated to it
+    pub stake_deposit_deactivated_lamports: u64,
+    /// Lamports deactivated from validators holding more than their allocation target
+    pub rebalance_deactivated_lamports: u64,
+    pub validators_entered: u64,
+    pub validators_exited: u64,
+    pub validators_instantly_unstaked: u64,
+    /// Estimated rewards the stake would have earned had it not been activating or
+    /// deactivating
+    pub forgone_rewards_lamports: u64,
+    /// Validators scored at the start of the cycle, one `compute_score` crank each
+    pub score_computations: u64,
+    /// Validators checked for instant unstaking, one `compute_instant_unstake` crank each
+    pub instant_unstake_checks: u64,
+    /// Stake increases and decreases, one `rebalance` crank each
+    pub stake_operations: u64,
+}
+
+impl CycleTurnover {
+    pub fn deactivated_lamports(&self) -> u64 {
+        self.scoring_deactivated_lamports
+            + self.instant_unstake_deactivated_lamports
+            + self.stake_deposit_deactivated_lamports
+            + self.rebalance_deactivated_lamports
+    }
+
+    pub fn crank_transactions(&self) -> u64 {
+        self.score_computations + self.instant_unstake_checks + self.stake_operations
+    }
+
+    pub fn estimated_crank_cost_lamports(&self, transaction_fee_lamports: u64) -> u64 {
+        self.crank_transactions()
+            .saturating_mul(transaction_fee_lamports)
+    }
+}
+
+impl AddAssign<&CycleTurnover> for CycleTurnover {
+    fn add_assign(&mut self, other: &CycleTurnover) {
+        self.scoring_deactivated_lamports += other.scoring_deactivated_lamports;
+        self.instant_unstake_deactivated_lamports += other.instant_unstake_deactivated_lamports;
+        self.stake_deposit_deactivated_lamports += other.stake_deposit_deactivated_lamports;
+        self.rebalance_deactivated_lamports += other.rebalance_deactivated_lamports;
+        self.validators_entered += other.validators_entered;
+        self.validators_exited += other.validators_exited;
+        self.validators_instantly_unstaked += other.validators_instantly_unstaked;
+        self.forgone_rewards_lamports += other.forgone_rewards_lamports;
+        self.score_computations += other.score_computations;
+        self.instant_unstake_checks += other.instant_unstake_checks;
+        self.stake_operations += other.stake_operations;
+    }
+}
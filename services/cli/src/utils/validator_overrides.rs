@@ -0,0 +1,102 @@
+use crate::error::CliError;
+use solana_sdk::pubkey::Pubkey;
+use std::{fs, path::Path, str::FromStr};
+
+/// A validator that is blacklisted or force included from `from_epoch` onwards
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidatorOverride {
+    pub vote_account: String,
+    pub from_epoch: u16,
+}
+
+impl ValidatorOverride {
+    pub fn is_active(&self, epoch: u16) -> bool {
+        epoch >= self.from_epoch
+    }
+}
+
+/// Reads a blacklist or force include file. Every non-empty line holds a vote account,
+/// optionally followed by the epoch the entry takes effect from (e.g. `<vote_account> 820`).
+/// Entries without an epoch apply to the whole simulation. Lines starting with `#` are ignored.
+pub fn read_validator_overrides(path: &Path) -> Result<Vec<ValidatorOverride>, CliError> {
+    let contents = fs::read_to_string(path)?;
+    parse_validator_overrides(&contents)
+}
+
+fn parse_validator_overrides(contents: &str) -> Result<Vec<ValidatorOverride>, CliError> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let mut parts = line.split_whitespace();
+            let vote_account = parts.next().unwrap_or_default();
+            Pubkey::from_str(vote_account)
+                .map_err(|_| CliError::InvalidValidatorOverride(line.to_string()))?;
+            let from_epoch = match parts.next() {
+                Some(epoch) => epoch
+                    .parse::<u16>()
+                    .map_err(|_| CliError::InvalidValidatorOverride(line.to_string()))?,
+                None => 0,
+            };
+            if parts.next().is_some() {
+                return Err(CliError::InvalidValidatorOverride(line.to_string()));
+            }
+
+            Ok(ValidatorOverride {
+                vote_account: vote_account.to_string(),
+                from_epoch,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_validator_overrides() {
+        let always = Pubkey::new_unique().to_string();
+        let scoped = Pubkey::new_unique().to_string();
+        let contents = format!("# blacklist\n\n{always}\n  {scoped} 820  \n");
+
+        let overrides = parse_validator_overrides(&contents).unwrap();
+        assert_eq!(
+            overrides,
+            vec![
+                ValidatorOverride {
+                    vote_account: always,
+                    from_epoch: 0,
+                },
+                ValidatorOverride {
+                    vote_account: scoped.clone(),
+                    from_epoch: 820,
+                },
+            ]
+        );
+
+        for invalid in [
+            "not-a-pubkey".to_string(),
+            format!("{scoped} epoch"),
+            format!("{scoped} 70000"),
+            format!("{scoped} 820 821"),
+        ] {
+            assert!(
+                parse_validator_overrides(&invalid).is_err(),
+                "{invalid} was accepted"
+            );
+        }
+    }
+
+    #[test]
+    fn test_override_is_active_from_its_epoch() {
+        let validator_override = ValidatorOverride {
+            vote_account: Pubkey::new_unique().to_string(),
+            from_epoch: 820,
+        };
+        assert!(!validator_override.is_active(819));
+        assert!(validator_override.is_active(820));
+        assert!(validator_override.is_active(821));
+    }
+}
@@ -1,54 +1,96 @@
-use crate::error::CliError;
-
 #[derive(Clone, Debug, Default)]
 pub struct ValidatorStakeState {
     pub active: u64,
     pub activating: u64,
     pub deactivating: u64,
+    /// Stake delegated from lamports that are still deactivating on another validator. It
+    /// starts activating once that deactivation completes at the end of the epoch.
+    pub pending_activation: u64,
     pub target: u64,
+    /// Net stake deposited directly on the validator this epoch
+    pub epoch_deposits: u64,
 }
 
 impl ValidatorStakeState {
     pub fn total(&self) -> u64 {
-        self.active + self.activating + self.deactivating
+        self.active + self.activating + self.deactivating + self.pending_activation
+    }
+
+    /// Stake delegated to the validator that isn't being deactivated
+    pub fn delegated(&self) -> u64 {
+        self.active + self.activating + self.pending_activation
     }
 
     pub fn add_activating_stake(&mut self, amount: u64) {
         self.activating += amount;
     }
 
-    pub fn add_deactivating_stake(&mut self, amount: u64) -> Result<(), CliError> {
-        if self.active < amount {
-            return Err(CliError::ArithmeticError);
+    /// Delegates lamports taken from stake deactivating on another validator. When
+    /// `wait_for_deactivation` is set the stake only starts activating next epoch, once the
+    /// lamports are back in the reserve.
+    pub fn add_redelegated_stake(&mut self, amount: u64, wait_for_deactivation: bool) {
+        if wait_for_deactivation {
+            self.pending_activation += amount;
+        } else {
+            self.activating += amount;
+        }
+    }
+
+    /// Deactivates up to `amount` lamports, taking stake that isn't active yet first. Returns
+    /// the amount deactivated.
+    pub fn deactivate(&mut self, amount: u64) -> u64 {
+        let mut remaining = amount;
+        for stake in [
+            &mut self.pending_activation,
+            &mut self.activating,
+            &mut self.active,
+        ] {
+            let deactivated = remaining.min(*stake);
+            *stake -= deactivated;
+            remaining -= deactivated;
         }
-        self.active -= amount;
-        self.deactivating += amount;
-        Ok(())
+        let deactivated = amount - remaining;
+        self.deactivating += deactivated;
+        deactivated
+    }
+
+    /// Deactivates all of the validator's stake. Returns the amount deactivated.
+    pub fn deactivate_all(&mut self) -> u64 {
+        self.deactivate(self.delegated())
     }
 
-    /// Process epoch transition: activating->active, deactivating->removed
+    /// Process epoch transition: activating->active, pending activation->activating,
+    /// deactivating->removed
     pub fn process_epoch_transition(&mut self) {
         // Activating stake becomes active
         self.active += self.activating;
-        self.activating = 0;
+        self.activating = std::mem::take(&mut self.pending_activation);
         // Deactivating stake is removed
         self.deactivating = 0;
+        self.epoch_deposits = 0;
     }
 
-    /// Apply stake change proportionally to active stake only
-    pub fn apply_stake_change(&mut self, ratio: f64) -> Result<(), CliError> {
-        if self.active == 0 {
-            return Ok(());
+    /// Apply a net deposit or withdrawal to active stake, never taking it below zero.
+    /// Returns the change that was actually applied.
+    pub fn apply_lamport_change(&mut self, lamports: i64) -> i64 {
+        if lamports >= 0 {
+            self.active += lamports as u64;
+            self.epoch_deposits += lamports as u64;
+            return lamports;
         }
-        let active_f64 = self.active as f64;
-        let adjustment = active_f64 * ratio;
-        let new_active = (active_f64 + adjustment).max(0.0) as u64;
-        self.active = new_active;
-        Ok(())
+        let removed = self.active.min(lamports.unsigned_abs());
+        self.active -= removed;
+        self.epoch_deposits = self.epoch_deposits.saturating_sub(removed);
+        -(removed as i64)
     }
 
     /// Apply rewards only to active stake
     pub fn apply_rewards(&mut self, reward_lamports: u64) {
         self.active += reward_lamports;
     }
+
+    /// Apply the final epoch's rewards of deactivating stake, which leave the validator with it
+    pub fn apply_deactivating_rewards(&mut self, reward_lamports: u64) {
+        self.deactivating += reward_lamports;
+    }
 }
@@ -0,0 +1,16 @@
+use crate::EpochRewardsTrackerError;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use sqlx::{Pool, Postgres};
+use stakenet_simulator_db::epoch_schedule::EpochSchedule;
+use tracing::info;
+
+pub async fn load_and_record_epoch_schedule(
+    db_connection: &Pool<Postgres>,
+    rpc_client: &RpcClient,
+) -> Result<(), EpochRewardsTrackerError> {
+    let epoch_schedule = rpc_client.get_epoch_schedule().await?;
+    info!("Upserting epoch schedule {:?}", epoch_schedule);
+    EpochSchedule::upsert(db_connection, epoch_schedule.into()).await?;
+
+    Ok(())
+}
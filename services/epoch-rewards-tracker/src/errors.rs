@@ -18,6 +18,15 @@ pub enum EpochRewardsTrackerError {
     #[error("ClusterHistoryNotFound: {0}")]
     ClusterHistoryNotFound(Pubkey),
 
+    #[error("Unable to deserialize stake pool account")]
+    StakePoolDeserializeError,
+
+    #[error("No steward config found for the JitoSOL stake pool")]
+    StewardConfigNotFound,
+
+    #[error("StewardStateNotFound: {0}")]
+    StewardStateNotFound(Pubkey),
+
     #[error("SqlxError: {0}")]
     SqlxError(#[from] SqlxError),
 
@@ -0,0 +1,123 @@
+use crate::{
+    EpochRewardsTrackerError,
+    steward_utils::{fetch_jito_sol_stake_pool, fetch_jito_sol_validator_list},
+};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use spl_stake_pool::state::ValidatorStakeInfo;
+use sqlx::{Pool, Postgres, types::BigDecimal};
+use stakenet_simulator_db::{
+    active_stake_jito_sol::ActiveStakeJitoSol, jito_sol_validator_stakes::JitoSolValidatorStake,
+};
+use tracing::info;
+
+/// Records the active and transient stake of every validator in the JitoSOL `ValidatorList`
+/// for the current epoch
+pub async fn load_and_record_validator_list(
+    db_connection: &Pool<Postgres>,
+    rpc_client: &RpcClient,
+) -> Result<(), EpochRewardsTrackerError> {
+    let (current_epoch, records) = fetch_validator_stakes(rpc_client).await?;
+
+    info!(
+        "Inserting {} JitoSOL validator stakes for epoch {}",
+        records.len(),
+        current_epoch
+    );
+    JitoSolValidatorStake::bulk_insert(db_connection, records).await?;
+
+    Ok(())
+}
+
+/// Records the pool's total active stake for the current epoch from the JitoSOL
+/// `ValidatorList`, replacing the value imported from Dune. Only stake that is fully active on
+/// the validators is counted, transient stake and the reserve are left out.
+pub async fn record_active_stake_from_validator_list(
+    db_connection: &Pool<Postgres>,
+    rpc_client: &RpcClient,
+) -> Result<(), EpochRewardsTrackerError> {
+    let (current_epoch, records) = fetch_validator_stakes(rpc_client).await?;
+    let total_active_lamports: u64 = records
+        .iter()
+        .map(|record| record.active_stake_lamports)
+        .sum();
+
+    info!("Upserting JitoSOL active stake for epoch {}", current_epoch);
+    ActiveStakeJitoSol::bulk_insert(
+        db_connection,
+        vec![ActiveStakeJitoSol::new(
+            current_epoch,
+            BigDecimal::from(total_active_lamports) / BigDecimal::from(LAMPORTS_PER_SOL),
+        )],
+    )
+    .await?;
+
+    Ok(())
+}
+
+async fn fetch_validator_stakes(
+    rpc_client: &RpcClient,
+) -> Result<(u64, Vec<JitoSolValidatorStake>), EpochRewardsTrackerError> {
+    let current_epoch = rpc_client.get_epoch_info().await?.epoch;
+    let stake_pool = fetch_jito_sol_stake_pool(rpc_client).await?;
+    let validator_list = fetch_jito_sol_validator_list(rpc_client, &stake_pool).await?;
+    Ok((
+        current_epoch,
+        validator_stakes(current_epoch, &validator_list.validators),
+    ))
+}
+
+fn validator_stakes(epoch: u64, validators: &[ValidatorStakeInfo]) -> Vec<JitoSolValidatorStake> {
+    validators
+        .iter()
+        .map(|validator| {
+            JitoSolValidatorStake::new(
+                epoch,
+                validator.vote_account_address.to_string(),
+                u64::from(validator.active_stake_lamports),
+                u64::from(validator.transient_stake_lamports),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+
+    #[test]
+    fn test_validator_stakes_keep_active_and_transient_apart() {
+        let validator = |active: u64, transient: u64| ValidatorStakeInfo {
+            active_stake_lamports: active.into(),
+            transient_stake_lamports: transient.into(),
+            vote_account_address: Pubkey::new_unique(),
+            ..Default::default()
+        };
+        let validators = [validator(5_000, 200), validator(0, 1_000)];
+
+        let records = validator_stakes(820, &validators);
+        assert_eq!(records.len(), 2);
+        for (record, validator) in records.iter().zip(&validators) {
+            let vote_pubkey = validator.vote_account_address.to_string();
+            assert_eq!(record.id, format!("820-{vote_pubkey}"));
+            assert_eq!(record.vote_pubkey, vote_pubkey);
+            assert_eq!(record.epoch, 820);
+        }
+        assert_eq!(
+            (
+                records[0].active_stake_lamports,
+                records[0].transient_stake_lamports
+            ),
+            (5_000, 200)
+        );
+        assert_eq!(
+            (
+                records[1].active_stake_lamports,
+                records[1].transient_stake_lamports
+            ),
+            (0, 1_000)
+        );
+        assert_eq!(records[1].total_stake_lamports(), 1_000);
+    }
+}
@@ -1,9 +1,20 @@
 use crate::{
-    cluster_history::load_and_record_cluster_history, config::Config,
-    errors::EpochRewardsTrackerError, inflation::gather_inflation_rewards,
-    priority_fees::gather_priority_fee_data_for_epoch, rpc_utils::fetch_slot_history,
+    cluster_history::load_and_record_cluster_history,
+    config::Config,
+    epoch_schedule::load_and_record_epoch_schedule,
+    errors::EpochRewardsTrackerError,
+    inflation::gather_inflation_rewards,
+    jito_sol_validator_list::{
+        load_and_record_validator_list, record_active_stake_from_validator_list,
+    },
+    priority_fees::gather_priority_fee_data_for_epoch,
+    rpc_utils::fetch_slot_history,
     stake_accounts::gather_stake_accounts,
+    stake_pool_snapshots::load_and_record_stake_pool_snapshot,
+    steward_config_history::load_and_record_steward_config,
+    steward_state::load_and_record_steward_state,
     validator_history_utils::load_and_record_validator_history,
+    vote_account_identities::load_and_record_vote_account_identities,
 };
 use clap::{Parser, Subcommand};
 use solana_client::nonblocking::rpc_client::RpcClient;
@@ -16,15 +27,21 @@ use tracing_subscriber::EnvFilter;
 mod cluster_history;
 mod config;
 mod dune;
+mod epoch_schedule;
 mod errors;
 mod fetch_active_stake;
 mod fetch_inactive_stake;
 mod inflation;
+mod jito_sol_validator_list;
 mod priority_fees;
 mod rpc_utils;
 mod stake_accounts;
+mod stake_pool_snapshots;
+mod steward_config_history;
+mod steward_state;
 mod steward_utils;
 mod validator_history_utils;
+mod vote_account_identities;
 mod withdraw_and_deposits;
 
 #[derive(Parser, Debug)]
@@ -59,6 +76,13 @@ struct Cli {
 pub enum Commands {
     FetchValidatorHistory,
     FetchClusterHistory,
+    FetchEpochSchedule,
+    FetchStewardConfig,
+    FetchStewardState,
+    FetchJitoSolValidatorList,
+    FetchActiveStakeFromValidatorList,
+    FetchStakePoolSnapshot,
+    FetchVoteAccountIdentities,
     GetStakeAccounts,
     GetInflationRewards,
     WithdrawAndDeposits,
@@ -115,6 +139,27 @@ async fn main() -> Result<(), EpochRewardsTrackerError> {
         Commands::FetchClusterHistory => {
             load_and_record_cluster_history(&db_conn_pool, &rpc_client).await?
         }
+        Commands::FetchEpochSchedule => {
+            load_and_record_epoch_schedule(&db_conn_pool, &rpc_client).await?
+        }
+        Commands::FetchStewardConfig => {
+            load_and_record_steward_config(&db_conn_pool, &rpc_client).await?
+        }
+        Commands::FetchStewardState => {
+            load_and_record_steward_state(&db_conn_pool, &rpc_client).await?
+        }
+        Commands::FetchJitoSolValidatorList => {
+            load_and_record_validator_list(&db_conn_pool, &rpc_client).await?
+        }
+        Commands::FetchActiveStakeFromValidatorList => {
+            record_active_stake_from_validator_list(&db_conn_pool, &rpc_client).await?
+        }
+        Commands::FetchStakePoolSnapshot => {
+            load_and_record_stake_pool_snapshot(&db_conn_pool, &rpc_client).await?
+        }
+        Commands::FetchVoteAccountIdentities => {
+            load_and_record_vote_account_identities(&db_conn_pool, &rpc_client).await?
+        }
         Commands::GetStakeAccounts => gather_stake_accounts(&db_conn_pool, &rpc_client).await?,
         Commands::GetInflationRewards => {
             gather_inflation_rewards(&db_conn_pool, &rpc_client).await?
@@ -3,7 +3,9 @@ use futures::future::join_all;
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::reward_type::RewardType;
 use sqlx::{Pool, Postgres};
-use stakenet_simulator_db::epoch_priority_fees::EpochPriorityFees;
+use stakenet_simulator_db::{
+    epoch_block_production::EpochBlockProduction, epoch_priority_fees::EpochPriorityFees,
+};
 use std::collections::HashSet;
 use tracing::{error, info};
 
@@ -60,26 +62,47 @@ pub async fn gather_priority_fee_data_for_epoch(
             results.extend(future_results);
         }
 
-        // Parse the block rewards and add to sum
+        // Parse the block rewards and add to sum, counting how every leader slot turned out
+        let leader_slots = results.len() as u32;
+        let (mut blocks_produced, mut skipped_slots, mut rpc_missing_slots) = (0u32, 0u32, 0u32);
         let total_fees = results
             .into_iter()
             .map(|(slot, get_block_result)| match get_block_result {
-                Ok(block) => block
-                    .rewards
-                    .unwrap()
-                    .into_iter()
-                    .filter(|r| r.reward_type == Some(RewardType::Fee))
-                    .map(|r| r.lamports as u64)
-                    .sum::<u64>(),
+                Ok(block) => {
+                    blocks_produced += 1;
+                    block
+                        .rewards
+                        .unwrap()
+                        .into_iter()
+                        .filter(|r| r.reward_type == Some(RewardType::Fee))
+                        .map(|r| r.lamports as u64)
+                        .sum::<u64>()
+                }
                 Err(e) => match e {
-                    RpcUtilsError::SkippedBlock => 0,
+                    RpcUtilsError::SkippedBlock => {
+                        skipped_slots += 1;
+                        0
+                    }
                     _ => {
                         error!("Error with block {}: {:>}", slot, e);
+                        rpc_missing_slots += 1;
                         0
                     }
                 },
             })
             .sum::<u64>();
+        EpochBlockProduction::bulk_insert(
+            &db_connection,
+            vec![EpochBlockProduction::new(
+                identity.clone(),
+                epoch,
+                leader_slots,
+                blocks_produced,
+                skipped_slots,
+                rpc_missing_slots,
+            )],
+        )
+        .await?;
         EpochPriorityFees::bulk_insert(
             &db_connection,
             vec![EpochPriorityFees::new(identity, epoch, total_fees)],
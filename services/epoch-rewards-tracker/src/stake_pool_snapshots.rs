@@ -0,0 +1,42 @@
+use crate::{EpochRewardsTrackerError, steward_utils::fetch_jito_sol_stake_pool};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use sqlx::{Pool, Postgres};
+use stakenet_simulator_db::stake_pool_snapshots::StakePoolSnapshot;
+use tracing::info;
+
+/// Records the JitoSOL stake pool's exchange rate, reserve balance and fees for the current epoch
+pub async fn load_and_record_stake_pool_snapshot(
+    db_connection: &Pool<Postgres>,
+    rpc_client: &RpcClient,
+) -> Result<(), EpochRewardsTrackerError> {
+    let current_epoch = rpc_client.get_epoch_info().await?.epoch;
+    let stake_pool = fetch_jito_sol_stake_pool(rpc_client).await?;
+    let reserve_lamports = rpc_client.get_balance(&stake_pool.reserve_stake).await?;
+
+    let snapshot = StakePoolSnapshot {
+        epoch: current_epoch,
+        total_lamports: stake_pool.total_lamports,
+        pool_token_supply: stake_pool.pool_token_supply,
+        reserve_lamports,
+        epoch_fee_numerator: stake_pool.epoch_fee.numerator,
+        epoch_fee_denominator: stake_pool.epoch_fee.denominator,
+        stake_deposit_fee_numerator: stake_pool.stake_deposit_fee.numerator,
+        stake_deposit_fee_denominator: stake_pool.stake_deposit_fee.denominator,
+        stake_withdrawal_fee_numerator: stake_pool.stake_withdrawal_fee.numerator,
+        stake_withdrawal_fee_denominator: stake_pool.stake_withdrawal_fee.denominator,
+        sol_deposit_fee_numerator: stake_pool.sol_deposit_fee.numerator,
+        sol_deposit_fee_denominator: stake_pool.sol_deposit_fee.denominator,
+        sol_withdrawal_fee_numerator: stake_pool.sol_withdrawal_fee.numerator,
+        sol_withdrawal_fee_denominator: stake_pool.sol_withdrawal_fee.denominator,
+        stake_referral_fee: stake_pool.stake_referral_fee,
+        sol_referral_fee: stake_pool.sol_referral_fee,
+    };
+
+    info!(
+        "Upserting stake pool snapshot for epoch {}: {} lamports, {} pool tokens",
+        current_epoch, snapshot.total_lamports, snapshot.pool_token_supply
+    );
+    StakePoolSnapshot::upsert(db_connection, snapshot).await?;
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use crate::{EpochRewardsTrackerError, steward_utils::fetch_steward_configs};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use sqlx::{Pool, Postgres};
+use stakenet_simulator_db::steward_config_history::StewardConfigHistory;
+use tracing::info;
+
+/// Records the current steward config parameters for the current epoch. Meant to be run every
+/// epoch so the simulator can replay parameter changes.
+pub async fn load_and_record_steward_config(
+    db_connection: &Pool<Postgres>,
+    rpc_client: &RpcClient,
+) -> Result<(), EpochRewardsTrackerError> {
+    let current_epoch = rpc_client.get_epoch_info().await?.epoch as u16;
+    let (config_pubkey, config) = fetch_steward_configs(rpc_client)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or(EpochRewardsTrackerError::StewardConfigNotFound)?;
+
+    info!(
+        "Upserting steward config {} parameters for epoch {}",
+        config_pubkey, current_epoch
+    );
+    StewardConfigHistory::upsert(
+        db_connection,
+        StewardConfigHistory::from_parameters(current_epoch, &config.parameters),
+    )
+    .await?;
+
+    Ok(())
+}
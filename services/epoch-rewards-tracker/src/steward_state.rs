@@ -0,0 +1,80 @@
+use crate::{
+    EpochRewardsTrackerError,
+    steward_utils::{
+        STEWARD_PROGRAM, fetch_jito_sol_stake_pool, fetch_jito_sol_validator_list,
+        fetch_steward_configs,
+    },
+};
+use anchor_lang::AccountDeserialize;
+use jito_steward::StewardStateAccount;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use sqlx::{Pool, Postgres};
+use stakenet_simulator_db::steward_validator_states::StewardValidatorState;
+use tracing::info;
+
+/// Records the scores, yield scores, delegations, instant unstake flags and progress of every
+/// validator in the on-chain steward state for the current epoch
+pub async fn load_and_record_steward_state(
+    db_connection: &Pool<Postgres>,
+    rpc_client: &RpcClient,
+) -> Result<(), EpochRewardsTrackerError> {
+    let (config_pubkey, config) = fetch_steward_configs(rpc_client)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or(EpochRewardsTrackerError::StewardConfigNotFound)?;
+
+    let (steward_state_pubkey, _) = Pubkey::find_program_address(
+        &[StewardStateAccount::SEED, config_pubkey.as_ref()],
+        &STEWARD_PROGRAM,
+    );
+    info!("Fetching StewardStateAccount at {}", steward_state_pubkey);
+    let account = rpc_client
+        .get_account(&steward_state_pubkey)
+        .await
+        .map_err(|_| EpochRewardsTrackerError::StewardStateNotFound(steward_state_pubkey))?;
+    let steward_state_account = StewardStateAccount::try_deserialize(&mut account.data.as_slice())
+        .map_err(|_| EpochRewardsTrackerError::StewardStateNotFound(steward_state_pubkey))?;
+    let state = &steward_state_account.state;
+
+    let stake_pool = fetch_jito_sol_stake_pool(rpc_client).await?;
+    let validator_list = fetch_jito_sol_validator_list(rpc_client, &stake_pool).await?;
+
+    let epoch = state.current_epoch;
+    // Scores are computed at the start of each cycle and kept until the next one
+    let cycle_start_epoch = state
+        .next_cycle_epoch
+        .saturating_sub(config.parameters.num_epochs_between_scoring);
+
+    let records: Vec<StewardValidatorState> = validator_list
+        .validators
+        .iter()
+        .enumerate()
+        .take(state.num_pool_validators as usize)
+        .map(|(index, validator)| {
+            StewardValidatorState::new(
+                epoch,
+                cycle_start_epoch,
+                validator.vote_account_address.to_string(),
+                index as u32,
+                state.scores[index],
+                state.yield_scores[index],
+                state.delegations[index].numerator,
+                state.delegations[index].denominator,
+                state.instant_unstake.get(index).unwrap_or_default(),
+                state.progress.get(index).unwrap_or_default(),
+            )
+        })
+        .collect();
+
+    info!(
+        "Inserting {} steward validator states for epoch {} (cycle starting at epoch {})",
+        records.len(),
+        epoch,
+        cycle_start_epoch
+    );
+    StewardValidatorState::bulk_insert(db_connection, records).await?;
+
+    Ok(())
+}
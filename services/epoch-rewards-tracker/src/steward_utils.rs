@@ -1,4 +1,5 @@
 use anchor_lang::{AccountDeserialize, Discriminator};
+use borsh::BorshDeserialize;
 use jito_steward::Config;
 use solana_account_decoder_client_types::UiAccountEncoding;
 use solana_client::{
@@ -6,8 +7,11 @@ use solana_client::{
     rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
     rpc_filter::{Memcmp, MemcmpEncodedBytes, RpcFilterType},
 };
+use solana_sdk::borsh1::try_from_slice_unchecked;
 use solana_sdk::pubkey;
 use solana_sdk::pubkey::Pubkey;
+use spl_stake_pool::state::{StakePool, ValidatorList};
+use std::str::FromStr;
 use tracing::info;
 
 use crate::EpochRewardsTrackerError;
@@ -16,9 +20,30 @@ pub const JITO_SOL_STAKE_POOL_ADDRESS: &str = "Jito4APyf642JPZPx3hGc6WWJ8zPKtRbR
 pub const STAKE_POOL_PROGRAM: Pubkey = pubkey!("SPoo1Ku8WFXoNDMHPsrGSTSG1Y47rzgn41SLUNakuHy");
 pub const STEWARD_PROGRAM: Pubkey = pubkey!("Stewardf95sJbmtcZsyagb2dg4Mo8eVQho8gpECvLx8");
 
-pub async fn fetch_and_log_steward_config(
+pub async fn fetch_jito_sol_stake_pool(
     rpc_client: &RpcClient,
-) -> Result<(), EpochRewardsTrackerError> {
+) -> Result<StakePool, EpochRewardsTrackerError> {
+    let stake_pool_address = Pubkey::from_str(JITO_SOL_STAKE_POOL_ADDRESS)?;
+    let account = rpc_client.get_account(&stake_pool_address).await?;
+    try_from_slice_unchecked::<StakePool>(&account.data)
+        .map_err(|_| EpochRewardsTrackerError::StakePoolDeserializeError)
+}
+
+pub async fn fetch_jito_sol_validator_list(
+    rpc_client: &RpcClient,
+    stake_pool: &StakePool,
+) -> Result<ValidatorList, EpochRewardsTrackerError> {
+    let account = rpc_client.get_account(&stake_pool.validator_list).await?;
+    // The account is preallocated for the maximum number of validators, so only the used part
+    // is deserialized
+    ValidatorList::deserialize(&mut account.data.as_slice())
+        .map_err(|_| EpochRewardsTrackerError::StakePoolDeserializeError)
+}
+
+/// Fetches the steward config accounts managing the JitoSOL stake pool
+pub async fn fetch_steward_configs(
+    rpc_client: &RpcClient,
+) -> Result<Vec<(Pubkey, Config)>, EpochRewardsTrackerError> {
     let discriminator_filter: RpcFilterType =
         RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, &Config::DISCRIMINATOR));
     let stake_pool_filter = RpcFilterType::Memcmp(Memcmp::new(
@@ -40,11 +65,24 @@ pub async fn fetch_and_log_steward_config(
         .get_program_accounts_with_config(&STEWARD_PROGRAM, config)
         .await?;
 
-    info!("found {} config accounts", accounts.len());
+    Ok(accounts
+        .iter()
+        .map(|(pubkey, account)| {
+            let mut data: &[u8] = &account.data;
+            let config = Config::try_deserialize(&mut data).unwrap();
+            (*pubkey, config)
+        })
+        .collect())
+}
+
+pub async fn fetch_and_log_steward_config(
+    rpc_client: &RpcClient,
+) -> Result<(), EpochRewardsTrackerError> {
+    let configs = fetch_steward_configs(rpc_client).await?;
+
+    info!("found {} config accounts", configs.len());
 
-    accounts.iter().for_each(|(pubkey, account)| {
-        let mut data: &[u8] = &account.data;
-        let config = Config::try_deserialize(&mut data).unwrap();
+    configs.iter().for_each(|(pubkey, config)| {
         info!("Config: {} | admin {}", pubkey, config.admin);
     });
 
@@ -0,0 +1,87 @@
+use crate::EpochRewardsTrackerError;
+use solana_client::{nonblocking::rpc_client::RpcClient, rpc_response::RpcVoteAccountStatus};
+use sqlx::{Pool, Postgres};
+use stakenet_simulator_db::vote_account_identities::VoteAccountIdentity;
+use tracing::info;
+
+/// Records the node identity of every current and delinquent vote account for the current
+/// epoch, so the priority fees recorded by identity can be joined with vote accounts
+pub async fn load_and_record_vote_account_identities(
+    db_connection: &Pool<Postgres>,
+    rpc_client: &RpcClient,
+) -> Result<(), EpochRewardsTrackerError> {
+    let current_epoch = rpc_client.get_epoch_info().await?.epoch;
+    let vote_accounts = rpc_client.get_vote_accounts().await?;
+
+    let records = vote_account_identities(current_epoch, vote_accounts);
+
+    info!(
+        "Inserting {} vote account identities for epoch {}",
+        records.len(),
+        current_epoch
+    );
+    VoteAccountIdentity::bulk_insert(db_connection, records).await?;
+
+    Ok(())
+}
+
+/// The identity of every current and delinquent vote account at `epoch`
+fn vote_account_identities(
+    epoch: u64,
+    vote_accounts: RpcVoteAccountStatus,
+) -> Vec<VoteAccountIdentity> {
+    vote_accounts
+        .current
+        .into_iter()
+        .chain(vote_accounts.delinquent)
+        .map(|vote_account| {
+            VoteAccountIdentity::new(vote_account.vote_pubkey, vote_account.node_pubkey, epoch)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_client::rpc_response::RpcVoteAccountInfo;
+
+    fn vote_account(vote_pubkey: &str, node_pubkey: &str) -> RpcVoteAccountInfo {
+        RpcVoteAccountInfo {
+            vote_pubkey: vote_pubkey.to_string(),
+            node_pubkey: node_pubkey.to_string(),
+            activated_stake: 0,
+            commission: 0,
+            epoch_vote_account: true,
+            epoch_credits: Vec::new(),
+            last_vote: 0,
+            root_slot: 0,
+        }
+    }
+
+    #[test]
+    fn test_delinquent_vote_accounts_are_recorded() {
+        let records = vote_account_identities(
+            820,
+            RpcVoteAccountStatus {
+                current: vec![vote_account("vote-a", "identity-a")],
+                delinquent: vec![vote_account("vote-b", "identity-b")],
+            },
+        );
+
+        let identities: Vec<(&str, &str, u64)> = records
+            .iter()
+            .map(|record| {
+                (
+                    record.vote_pubkey.as_str(),
+                    record.identity_pubkey.as_str(),
+                    record.epoch,
+                )
+            })
+            .collect();
+        assert_eq!(
+            identities,
+            vec![("vote-a", "identity-a", 820), ("vote-b", "identity-b", 820)]
+        );
+        assert_eq!(records[0].id, "820-vote-a");
+    }
+}
@@ -7,7 +7,7 @@ use sqlx::{
 };
 use validator_history::{CircBufCluster, ClusterHistory as JitoClusterHistory};
 
-#[derive(FromRow)]
+#[derive(FromRow, Clone)]
 pub struct ClusterHistory {
     #[sqlx(try_from = "BigDecimalU64")]
     pub struct_version: u64,
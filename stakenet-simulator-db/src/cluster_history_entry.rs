@@ -3,7 +3,7 @@ use crate::big_decimal_u64::BigDecimalU64;
 use sqlx::{Error as SqlxError, Pool, Postgres, QueryBuilder, prelude::FromRow, types::BigDecimal};
 use validator_history::ClusterHistoryEntry as JitoClusterHistoryEntry;
 
-#[derive(FromRow)]
+#[derive(FromRow, Clone)]
 pub struct ClusterHistoryEntry {
     /// Epoch number
     #[sqlx(try_from = "i32")]
@@ -92,4 +92,14 @@ impl ClusterHistoryEntry {
             .fetch_all(db_connection)
             .await
     }
+
+    pub async fn fetch_for_epoch(
+        db_connection: &Pool<Postgres>,
+        epoch: u16,
+    ) -> Result<Option<Self>, SqlxError> {
+        sqlx::query_as::<_, Self>("SELECT * FROM cluster_history_entries WHERE epoch = $1")
+            .bind(i32::from(epoch))
+            .fetch_optional(db_connection)
+            .await
+    }
 }
@@ -0,0 +1,160 @@
+use crate::big_decimal_u64::BigDecimalU64;
+use crate::vote_account_identities::{VoteAccountIdentity, join_on_identity};
+use sqlx::{Error, FromRow, Pool, Postgres, QueryBuilder, types::BigDecimal};
+
+/// How a leader's slots in an epoch turned out
+#[derive(Clone, Debug, FromRow)]
+pub struct EpochBlockProduction {
+    pub id: String, // {epoch}-{identity_pubkey}
+    pub identity_pubkey: String,
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub epoch: u64,
+    #[sqlx(try_from = "i64")]
+    pub leader_slots: u32,
+    #[sqlx(try_from = "i64")]
+    pub blocks_produced: u32,
+    #[sqlx(try_from = "i64")]
+    pub skipped_slots: u32,
+    /// Slots the RPC returned no block for without confirming they were skipped
+    #[sqlx(try_from = "i64")]
+    pub rpc_missing_slots: u32,
+}
+
+impl EpochBlockProduction {
+    const NUM_FIELDS: u8 = 7;
+    // Based on the bind limit of postgres
+    const INSERT_CHUNK_SIZE: usize = 65534 / Self::NUM_FIELDS as usize;
+    const INSERT_QUERY: &str = "INSERT INTO epoch_block_production \
+        (id, identity_pubkey, epoch, leader_slots, blocks_produced, skipped_slots, rpc_missing_slots) VALUES ";
+
+    pub fn new(
+        identity: String,
+        epoch: u64,
+        leader_slots: u32,
+        blocks_produced: u32,
+        skipped_slots: u32,
+        rpc_missing_slots: u32,
+    ) -> Self {
+        Self {
+            id: format!("{}-{}", epoch, identity),
+            identity_pubkey: identity,
+            epoch,
+            leader_slots,
+            blocks_produced,
+            skipped_slots,
+            rpc_missing_slots,
+        }
+    }
+
+    pub async fn bulk_insert(
+        db_connection: &Pool<Postgres>,
+        records: Vec<Self>,
+    ) -> Result<(), Error> {
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        let mut query_builder: QueryBuilder<Postgres> = QueryBuilder::new(Self::INSERT_QUERY);
+        let mut num_records: usize = 0;
+
+        for record in records.into_iter() {
+            num_records += 1;
+            if num_records > 1 {
+                query_builder.push(", (");
+            } else {
+                query_builder.push("(");
+            }
+
+            let mut separated = query_builder.separated(", ");
+            separated.push_bind(record.id);
+            separated.push_bind(record.identity_pubkey);
+            separated.push_bind(BigDecimal::from(record.epoch));
+            separated.push_bind(i64::from(record.leader_slots));
+            separated.push_bind(i64::from(record.blocks_produced));
+            separated.push_bind(i64::from(record.skipped_slots));
+            separated.push_bind(i64::from(record.rpc_missing_slots));
+            separated.push_unseparated(") ");
+
+            if num_records >= Self::INSERT_CHUNK_SIZE {
+                query_builder.push(" ON CONFLICT (id) DO NOTHING");
+                let query = query_builder.build();
+                query.execute(db_connection).await?;
+                num_records = 0;
+                query_builder = QueryBuilder::new(Self::INSERT_QUERY);
+            }
+        }
+
+        if num_records > 0 {
+            query_builder.push(" ON CONFLICT (id) DO NOTHING");
+            let query = query_builder.build();
+            query.execute(db_connection).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn fetch_for_epoch_range(
+        db_connection: &Pool<Postgres>,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<Self>, Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT * FROM epoch_block_production WHERE epoch BETWEEN $1 AND $2",
+        )
+        .bind(BigDecimal::from(start_epoch))
+        .bind(BigDecimal::from(end_epoch))
+        .fetch_all(db_connection)
+        .await
+    }
+
+    /// Block production of the vote accounts between `start_epoch` and `end_epoch`, joined on
+    /// the identities recorded in `vote_account_identities`. Epochs without a recorded identity
+    /// for the vote account are left out.
+    pub async fn fetch_for_vote_accounts_and_epochs(
+        db_connection: &Pool<Postgres>,
+        vote_accounts: &Vec<String>,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<VoteAccountBlockProduction>, Error> {
+        let identities = VoteAccountIdentity::fetch_for_vote_accounts_and_epochs(
+            db_connection,
+            vote_accounts,
+            start_epoch,
+            end_epoch,
+        )
+        .await?;
+        let block_production =
+            Self::fetch_for_epoch_range(db_connection, start_epoch, end_epoch).await?;
+
+        Ok(
+            join_on_identity(&identities, &block_production, |production| {
+                (production.identity_pubkey.as_str(), production.epoch)
+            })
+            .into_iter()
+            .map(|(vote_pubkey, production)| VoteAccountBlockProduction {
+                vote_pubkey,
+                epoch: production.epoch,
+                leader_slots: production.leader_slots,
+                skipped_slots: production.skipped_slots,
+                rpc_missing_slots: production.rpc_missing_slots,
+            })
+            .collect(),
+        )
+    }
+}
+
+/// Block production of the node identity a vote account voted for in an epoch
+#[derive(Clone, Debug)]
+pub struct VoteAccountBlockProduction {
+    pub vote_pubkey: String,
+    pub epoch: u64,
+    pub leader_slots: u32,
+    pub skipped_slots: u32,
+    pub rpc_missing_slots: u32,
+}
+
+impl VoteAccountBlockProduction {
+    /// Leader slots whose outcome is known, leaving out the slots missing on the RPC
+    pub fn known_slots(&self) -> u32 {
+        self.leader_slots.saturating_sub(self.rpc_missing_slots)
+    }
+}
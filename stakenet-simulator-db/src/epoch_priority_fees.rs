@@ -1,4 +1,5 @@
 use crate::big_decimal_u64::BigDecimalU64;
+use crate::vote_account_identities::{VoteAccountIdentity, join_on_identity};
 use sqlx::{Error, FromRow, Pool, Postgres, QueryBuilder, types::BigDecimal};
 
 #[derive(Debug, FromRow)]
@@ -87,6 +88,72 @@ impl EpochPriorityFees {
 
         Ok(pubkeys.into_iter().map(|row| row.identity_pubkey).collect())
     }
+
+    pub async fn fetch_for_epoch_range(
+        db_connection: &Pool<Postgres>,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<Self>, Error> {
+        sqlx::query_as::<_, Self>("SELECT * FROM epoch_priority_fees WHERE epoch BETWEEN $1 AND $2")
+            .bind(BigDecimal::from(start_epoch))
+            .bind(BigDecimal::from(end_epoch))
+            .fetch_all(db_connection)
+            .await
+    }
+
+    /// Priority fees of the vote accounts between `start_epoch` and `end_epoch`, joined on the
+    /// identities recorded in `vote_account_identities`. Epochs without a recorded identity for
+    /// the vote account are left out.
+    pub async fn fetch_for_vote_accounts_and_epochs(
+        db_connection: &Pool<Postgres>,
+        vote_accounts: &Vec<String>,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<VoteAccountPriorityFees>, Error> {
+        let identities = VoteAccountIdentity::fetch_for_vote_accounts_and_epochs(
+            db_connection,
+            vote_accounts,
+            start_epoch,
+            end_epoch,
+        )
+        .await?;
+        let priority_fees =
+            Self::fetch_for_epoch_range(db_connection, start_epoch, end_epoch).await?;
+
+        Ok(join_on_identity(&identities, &priority_fees, |fees| {
+            (fees.identity_pubkey.as_str(), fees.epoch)
+        })
+        .into_iter()
+        .map(|(vote_pubkey, fees)| VoteAccountPriorityFees {
+            vote_pubkey,
+            epoch: fees.epoch,
+            priority_fees: fees.priority_fees,
+        })
+        .collect())
+    }
+
+    /// Sum of the priority fees paid in every block of the epoch
+    pub async fn fetch_total_for_epoch(
+        db_connection: &Pool<Postgres>,
+        epoch: u64,
+    ) -> Result<u64, Error> {
+        let total: BigDecimalU64 = sqlx::query_scalar(
+            "SELECT COALESCE(SUM(priority_fees), 0) FROM epoch_priority_fees WHERE epoch = $1",
+        )
+        .bind(BigDecimal::from(epoch))
+        .fetch_one(db_connection)
+        .await?;
+
+        Ok(total.into())
+    }
+}
+
+/// Priority fees earned by the node identity a vote account voted for in an epoch
+#[derive(Clone, Debug)]
+pub struct VoteAccountPriorityFees {
+    pub vote_pubkey: String,
+    pub epoch: u64,
+    pub priority_fees: u64,
 }
 
 #[derive(FromRow)]
@@ -5,7 +5,7 @@ use sqlx::{Error, FromRow, Pool, Postgres, QueryBuilder, types::BigDecimal};
 
 const MAX_BPS: u64 = 10_000;
 
-#[derive(FromRow)]
+#[derive(FromRow, Clone)]
 pub struct EpochRewards {
     pub id: String,
     pub vote_pubkey: String,
@@ -177,6 +177,66 @@ impl EpochRewards {
             + mev_rewards as u64
             + priority_fee_rewards as u64
     }
+
+    /// Like `stake_after_epoch`, but for simulations where the pool's stake on the validator
+    /// differs from what it actually delegated. The validator's stake is recomputed as
+    /// `active_stake - actual_pool_stake + current_active_stake`. Inflation rewards are earned
+    /// per lamport so they are unaffected, while MEV and priority fees are fixed per epoch and
+    /// get diluted (or concentrated) across the recomputed stake.
+    pub fn stake_after_epoch_with_dilution(
+        &self,
+        current_active_stake: u64,
+        actual_pool_stake: u64,
+    ) -> u64 {
+        let simulated_active_stake = self
+            .active_stake
+            .saturating_sub(actual_pool_stake)
+            .saturating_add(current_active_stake);
+        if self.active_stake == 0 || simulated_active_stake == 0 {
+            return current_active_stake;
+        }
+
+        let inflation_for_stakers = self.total_inflation_rewards
+            * (MAX_BPS - u64::from(self.inflation_commission_bps))
+            / MAX_BPS;
+        let inflation_rewards = u128::from(inflation_for_stakers)
+            * u128::from(current_active_stake)
+            / u128::from(self.active_stake);
+
+        let mev_for_stakers =
+            self.total_mev_rewards * (MAX_BPS - u64::from(self.mev_commission_bps)) / MAX_BPS;
+        let mev_rewards = u128::from(mev_for_stakers) * u128::from(current_active_stake)
+            / u128::from(simulated_active_stake);
+
+        let priority_fee_for_stakers = self.total_priority_fee_rewards
+            * (MAX_BPS - u64::from(self.priority_fee_commission_bps))
+            / MAX_BPS;
+        let priority_fee_rewards = u128::from(priority_fee_for_stakers)
+            * u128::from(current_active_stake)
+            / u128::from(simulated_active_stake);
+
+        current_active_stake
+            + inflation_rewards as u64
+            + mev_rewards as u64
+            + priority_fee_rewards as u64
+    }
+}
+
+/// Rewards without commissions or rewards, for tests to fill in with struct update syntax
+#[cfg(any(test, feature = "test-utils"))]
+pub fn test_epoch_rewards(vote_pubkey: &str, epoch: u64, active_stake: u64) -> EpochRewards {
+    EpochRewards {
+        id: String::new(),
+        vote_pubkey: vote_pubkey.to_string(),
+        epoch,
+        inflation_commission_bps: 0,
+        total_inflation_rewards: 0,
+        mev_commission_bps: 0,
+        total_mev_rewards: 0,
+        priority_fee_commission_bps: 0,
+        total_priority_fee_rewards: 0,
+        active_stake,
+    }
 }
 
 #[cfg(test)]
@@ -188,19 +248,37 @@ mod tests {
     #[test]
     fn test_apy() {
         let rewards = EpochRewards {
-            id: "".to_string(),
-            vote_pubkey: Pubkey::new_unique().to_string(),
-            epoch: 1,
             inflation_commission_bps: 500,
             total_inflation_rewards: 1_000_000,
             mev_commission_bps: 1_000,
             total_mev_rewards: 1_000_000,
             priority_fee_commission_bps: 10_000,
             total_priority_fee_rewards: 1_000_000,
-            active_stake: 1_000_000_000,
+            ..test_epoch_rewards(&Pubkey::new_unique().to_string(), 1, 1_000_000_000)
         };
 
         let actual = rewards.apy();
         assert_eq!(actual, Some(0.337625))
     }
+
+    #[test]
+    fn test_stake_after_epoch_with_dilution() {
+        let rewards = EpochRewards {
+            total_inflation_rewards: 1_000_000,
+            total_mev_rewards: 1_000_000,
+            ..test_epoch_rewards(&Pubkey::new_unique().to_string(), 1, 1_000_000_000)
+        };
+
+        // Matches stake_after_epoch when the simulated stake equals the actual pool stake
+        assert_eq!(
+            rewards.stake_after_epoch_with_dilution(100_000_000, 100_000_000),
+            rewards.stake_after_epoch(100_000_000)
+        );
+
+        // Adding as much stake as the validator already has halves the MEV per lamport
+        assert_eq!(
+            rewards.stake_after_epoch_with_dilution(1_000_000_000, 0),
+            1_000_000_000 + 1_000_000 + 500_000
+        );
+    }
 }
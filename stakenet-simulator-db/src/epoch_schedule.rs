@@ -0,0 +1,96 @@
+use crate::big_decimal_u64::BigDecimalU64;
+use solana_sdk::epoch_schedule::EpochSchedule as SolanaEpochSchedule;
+use sqlx::{
+    Error as SqlxError, Pool, Postgres, postgres::PgQueryResult, prelude::FromRow,
+    types::BigDecimal,
+};
+
+/// The cluster's `EpochSchedule` sysvar, used for epoch to slot conversions
+#[derive(FromRow, Clone, Debug)]
+pub struct EpochSchedule {
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub slots_per_epoch: u64,
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub leader_schedule_slot_offset: u64,
+    pub warmup: bool,
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub first_normal_epoch: u64,
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub first_normal_slot: u64,
+}
+
+impl From<SolanaEpochSchedule> for EpochSchedule {
+    fn from(value: SolanaEpochSchedule) -> Self {
+        Self {
+            slots_per_epoch: value.slots_per_epoch,
+            leader_schedule_slot_offset: value.leader_schedule_slot_offset,
+            warmup: value.warmup,
+            first_normal_epoch: value.first_normal_epoch,
+            first_normal_slot: value.first_normal_slot,
+        }
+    }
+}
+
+impl From<EpochSchedule> for SolanaEpochSchedule {
+    fn from(value: EpochSchedule) -> Self {
+        Self {
+            slots_per_epoch: value.slots_per_epoch,
+            leader_schedule_slot_offset: value.leader_schedule_slot_offset,
+            warmup: value.warmup,
+            first_normal_epoch: value.first_normal_epoch,
+            first_normal_slot: value.first_normal_slot,
+        }
+    }
+}
+
+impl EpochSchedule {
+    pub async fn upsert(
+        db_connection: &Pool<Postgres>,
+        record: Self,
+    ) -> Result<PgQueryResult, SqlxError> {
+        let sql = "
+    INSERT INTO epoch_schedules (id,slots_per_epoch,leader_schedule_slot_offset,warmup,first_normal_epoch,first_normal_slot) VALUES ($1, $2, $3, $4, $5, $6) \
+    ON CONFLICT (id) DO UPDATE SET \
+    slots_per_epoch = EXCLUDED.slots_per_epoch,
+    leader_schedule_slot_offset = EXCLUDED.leader_schedule_slot_offset,
+    warmup = EXCLUDED.warmup,
+    first_normal_epoch = EXCLUDED.first_normal_epoch,
+    first_normal_slot = EXCLUDED.first_normal_slot
+    ";
+        sqlx::query(sql)
+            .bind(1)
+            .bind(BigDecimal::from(record.slots_per_epoch))
+            .bind(BigDecimal::from(record.leader_schedule_slot_offset))
+            .bind(record.warmup)
+            .bind(BigDecimal::from(record.first_normal_epoch))
+            .bind(BigDecimal::from(record.first_normal_slot))
+            .execute(db_connection)
+            .await
+    }
+
+    /// The recorded epoch schedule, `None` when `fetch-epoch-schedule` never ran
+    pub async fn fetch(db_connection: &Pool<Postgres>) -> Result<Option<Self>, SqlxError> {
+        sqlx::query_as::<_, Self>("SELECT slots_per_epoch, leader_schedule_slot_offset, warmup, first_normal_epoch, first_normal_slot FROM epoch_schedules WHERE id = 1")
+            .fetch_optional(db_connection)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_epoch_schedule_round_trips_through_the_record() {
+        for schedule in [
+            SolanaEpochSchedule::without_warmup(),
+            SolanaEpochSchedule::custom(8_192, 8_192, true),
+        ] {
+            let record = EpochSchedule::from(schedule.clone());
+            assert_eq!(record.slots_per_epoch, schedule.slots_per_epoch);
+            assert_eq!(record.warmup, schedule.warmup);
+            assert_eq!(record.first_normal_epoch, schedule.first_normal_epoch);
+            assert_eq!(SolanaEpochSchedule::from(record), schedule);
+        }
+    }
+}
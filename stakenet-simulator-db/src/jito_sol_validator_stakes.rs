@@ -0,0 +1,117 @@
+use crate::big_decimal_u64::BigDecimalU64;
+use sqlx::{Error, FromRow, Pool, Postgres, QueryBuilder, types::BigDecimal};
+
+/// Stake the JitoSOL pool had delegated to a validator at an epoch, as recorded in the stake
+/// pool's `ValidatorList`
+#[derive(FromRow, Clone, Debug)]
+pub struct JitoSolValidatorStake {
+    pub id: String, // {epoch}-{vote_pubkey}
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub epoch: u64,
+    pub vote_pubkey: String,
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub active_stake_lamports: u64,
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub transient_stake_lamports: u64,
+}
+
+impl JitoSolValidatorStake {
+    const NUM_FIELDS: u8 = 5;
+    // Based on the bind limit of postgres
+    const INSERT_CHUNK_SIZE: usize = 65534 / Self::NUM_FIELDS as usize;
+    const INSERT_QUERY: &str = "INSERT INTO jito_sol_validator_stakes \
+        (id, epoch, vote_pubkey, active_stake_lamports, transient_stake_lamports) VALUES ";
+    const ON_CONFLICT: &str = " ON CONFLICT (id) DO UPDATE SET \
+        active_stake_lamports = EXCLUDED.active_stake_lamports, \
+        transient_stake_lamports = EXCLUDED.transient_stake_lamports";
+
+    pub fn new(
+        epoch: u64,
+        vote_pubkey: String,
+        active_stake_lamports: u64,
+        transient_stake_lamports: u64,
+    ) -> Self {
+        let id = format!("{}-{}", epoch, vote_pubkey);
+        Self {
+            id,
+            epoch,
+            vote_pubkey,
+            active_stake_lamports,
+            transient_stake_lamports,
+        }
+    }
+
+    /// Active and transient stake delegated to the validator
+    pub fn total_stake_lamports(&self) -> u64 {
+        self.active_stake_lamports
+            .saturating_add(self.transient_stake_lamports)
+    }
+
+    pub async fn bulk_insert(
+        db_connection: &Pool<Postgres>,
+        records: Vec<Self>,
+    ) -> Result<(), Error> {
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        let mut query_builder: QueryBuilder<Postgres> = QueryBuilder::new(Self::INSERT_QUERY);
+        let mut num_records: usize = 0;
+
+        for record in records.into_iter() {
+            num_records += 1;
+            if num_records > 1 {
+                query_builder.push(", (");
+            } else {
+                query_builder.push("(");
+            }
+
+            let mut separated = query_builder.separated(", ");
+            separated.push_bind(record.id);
+            separated.push_bind(BigDecimal::from(record.epoch));
+            separated.push_bind(record.vote_pubkey);
+            separated.push_bind(BigDecimal::from(record.active_stake_lamports));
+            separated.push_bind(BigDecimal::from(record.transient_stake_lamports));
+            separated.push_unseparated(") ");
+
+            if num_records >= Self::INSERT_CHUNK_SIZE {
+                query_builder.push(Self::ON_CONFLICT);
+                let query = query_builder.build();
+                query.execute(db_connection).await?;
+                num_records = 0;
+                query_builder = QueryBuilder::new(Self::INSERT_QUERY);
+            }
+        }
+
+        if num_records > 0 {
+            query_builder.push(Self::ON_CONFLICT);
+            let query = query_builder.build();
+            query.execute(db_connection).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn fetch_for_epoch(
+        db_connection: &Pool<Postgres>,
+        epoch: u64,
+    ) -> Result<Vec<Self>, Error> {
+        sqlx::query_as::<_, Self>("SELECT * FROM jito_sol_validator_stakes WHERE epoch = $1")
+            .bind(BigDecimal::from(epoch))
+            .fetch_all(db_connection)
+            .await
+    }
+
+    pub async fn fetch_for_epoch_range(
+        db_connection: &Pool<Postgres>,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<Self>, Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT * FROM jito_sol_validator_stakes WHERE epoch BETWEEN $1 AND $2 ORDER BY epoch, vote_pubkey",
+        )
+        .bind(BigDecimal::from(start_epoch))
+        .bind(BigDecimal::from(end_epoch))
+        .fetch_all(db_connection)
+        .await
+    }
+}
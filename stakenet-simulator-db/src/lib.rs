@@ -4,15 +4,22 @@ pub mod active_stake_jito_sol;
 mod big_decimal_u64;
 pub mod cluster_history;
 pub mod cluster_history_entry;
+pub mod epoch_block_production;
 pub mod epoch_priority_fees;
 pub mod epoch_rewards;
+pub mod epoch_schedule;
 pub mod error;
 pub mod inactive_stake_jito_sol;
 pub mod inflation_rewards;
+pub mod jito_sol_validator_stakes;
 mod macros;
 pub mod stake_accounts;
+pub mod stake_pool_snapshots;
+pub mod steward_config_history;
+pub mod steward_validator_states;
 pub mod validator_history;
 pub mod validator_history_entry;
+pub mod vote_account_identities;
 pub mod withdraw_and_deposits;
 
 #[derive(Debug)]
@@ -0,0 +1,193 @@
+use crate::{big_decimal_u64::BigDecimalU64, cluster_history_entry::ClusterHistoryEntry};
+use sqlx::{
+    Error as SqlxError, Pool, Postgres, postgres::PgQueryResult, prelude::FromRow,
+    types::BigDecimal,
+};
+
+const SECONDS_PER_YEAR: f64 = 365.0 * 24.0 * 60.0 * 60.0;
+
+/// State of the JitoSOL stake pool account at an epoch
+#[derive(FromRow, Clone, Debug)]
+pub struct StakePoolSnapshot {
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub epoch: u64,
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub total_lamports: u64,
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub pool_token_supply: u64,
+    /// Lamports in the pool's reserve stake account
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub reserve_lamports: u64,
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub epoch_fee_numerator: u64,
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub epoch_fee_denominator: u64,
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub stake_deposit_fee_numerator: u64,
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub stake_deposit_fee_denominator: u64,
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub stake_withdrawal_fee_numerator: u64,
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub stake_withdrawal_fee_denominator: u64,
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub sol_deposit_fee_numerator: u64,
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub sol_deposit_fee_denominator: u64,
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub sol_withdrawal_fee_numerator: u64,
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub sol_withdrawal_fee_denominator: u64,
+    #[sqlx(try_from = "i16")]
+    pub stake_referral_fee: u8,
+    #[sqlx(try_from = "i16")]
+    pub sol_referral_fee: u8,
+}
+
+impl StakePoolSnapshot {
+    /// Lamports per pool token
+    pub fn exchange_rate(&self) -> Option<f64> {
+        if self.pool_token_supply == 0 {
+            return None;
+        }
+        Some(self.total_lamports as f64 / self.pool_token_supply as f64)
+    }
+
+    pub async fn upsert(
+        db_connection: &Pool<Postgres>,
+        record: Self,
+    ) -> Result<PgQueryResult, SqlxError> {
+        let sql = "
+    INSERT INTO stake_pool_snapshots (epoch,total_lamports,pool_token_supply,reserve_lamports,epoch_fee_numerator,epoch_fee_denominator,stake_deposit_fee_numerator,stake_deposit_fee_denominator,stake_withdrawal_fee_numerator,stake_withdrawal_fee_denominator,sol_deposit_fee_numerator,sol_deposit_fee_denominator,sol_withdrawal_fee_numerator,sol_withdrawal_fee_denominator,stake_referral_fee,sol_referral_fee) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16) \
+    ON CONFLICT (epoch) DO UPDATE SET \
+    total_lamports = EXCLUDED.total_lamports,
+    pool_token_supply = EXCLUDED.pool_token_supply,
+    reserve_lamports = EXCLUDED.reserve_lamports,
+    epoch_fee_numerator = EXCLUDED.epoch_fee_numerator,
+    epoch_fee_denominator = EXCLUDED.epoch_fee_denominator,
+    stake_deposit_fee_numerator = EXCLUDED.stake_deposit_fee_numerator,
+    stake_deposit_fee_denominator = EXCLUDED.stake_deposit_fee_denominator,
+    stake_withdrawal_fee_numerator = EXCLUDED.stake_withdrawal_fee_numerator,
+    stake_withdrawal_fee_denominator = EXCLUDED.stake_withdrawal_fee_denominator,
+    sol_deposit_fee_numerator = EXCLUDED.sol_deposit_fee_numerator,
+    sol_deposit_fee_denominator = EXCLUDED.sol_deposit_fee_denominator,
+    sol_withdrawal_fee_numerator = EXCLUDED.sol_withdrawal_fee_numerator,
+    sol_withdrawal_fee_denominator = EXCLUDED.sol_withdrawal_fee_denominator,
+    stake_referral_fee = EXCLUDED.stake_referral_fee,
+    sol_referral_fee = EXCLUDED.sol_referral_fee
+    ";
+        sqlx::query(sql)
+            .bind(BigDecimal::from(record.epoch))
+            .bind(BigDecimal::from(record.total_lamports))
+            .bind(BigDecimal::from(record.pool_token_supply))
+            .bind(BigDecimal::from(record.reserve_lamports))
+            .bind(BigDecimal::from(record.epoch_fee_numerator))
+            .bind(BigDecimal::from(record.epoch_fee_denominator))
+            .bind(BigDecimal::from(record.stake_deposit_fee_numerator))
+            .bind(BigDecimal::from(record.stake_deposit_fee_denominator))
+            .bind(BigDecimal::from(record.stake_withdrawal_fee_numerator))
+            .bind(BigDecimal::from(record.stake_withdrawal_fee_denominator))
+            .bind(BigDecimal::from(record.sol_deposit_fee_numerator))
+            .bind(BigDecimal::from(record.sol_deposit_fee_denominator))
+            .bind(BigDecimal::from(record.sol_withdrawal_fee_numerator))
+            .bind(BigDecimal::from(record.sol_withdrawal_fee_denominator))
+            .bind(i16::from(record.stake_referral_fee))
+            .bind(i16::from(record.sol_referral_fee))
+            .execute(db_connection)
+            .await
+    }
+
+    pub async fn fetch_for_epoch(
+        db_connection: &Pool<Postgres>,
+        epoch: u64,
+    ) -> Result<Option<Self>, SqlxError> {
+        sqlx::query_as::<_, Self>("SELECT * FROM stake_pool_snapshots WHERE epoch = $1")
+            .bind(BigDecimal::from(epoch))
+            .fetch_optional(db_connection)
+            .await
+    }
+
+    /// Returns the APY earned by pool token holders between `start_epoch` and `end_epoch`, from
+    /// the change in the pool's exchange rate. The period is annualized with the epochs' start
+    /// timestamps recorded in the cluster history. Returns `None` if either snapshot or
+    /// timestamp is missing.
+    pub async fn realized_holder_apy(
+        db_connection: &Pool<Postgres>,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Option<f64>, SqlxError> {
+        if end_epoch <= start_epoch {
+            return Ok(None);
+        }
+
+        let start = Self::fetch_for_epoch(db_connection, start_epoch).await?;
+        let end = Self::fetch_for_epoch(db_connection, end_epoch).await?;
+        let (Some(start_rate), Some(end_rate)) = (
+            start.and_then(|snapshot| snapshot.exchange_rate()),
+            end.and_then(|snapshot| snapshot.exchange_rate()),
+        ) else {
+            return Ok(None);
+        };
+
+        annualized_exchange_rate_change(db_connection, start_epoch, end_epoch, start_rate, end_rate)
+            .await
+    }
+}
+
+/// Compounds an exchange rate change between `start_epoch` and `end_epoch` to a year, with the
+/// epochs' start timestamps recorded in the cluster history. Returns `None` if either timestamp
+/// is missing.
+pub async fn annualized_exchange_rate_change(
+    db_connection: &Pool<Postgres>,
+    start_epoch: u64,
+    end_epoch: u64,
+    start_rate: f64,
+    end_rate: f64,
+) -> Result<Option<f64>, SqlxError> {
+    let (Some(start_timestamp), Some(end_timestamp)) = (
+        epoch_start_timestamp(db_connection, start_epoch).await?,
+        epoch_start_timestamp(db_connection, end_epoch).await?,
+    ) else {
+        return Ok(None);
+    };
+
+    Ok(annualized_rate_change(
+        start_rate,
+        end_rate,
+        end_timestamp.saturating_sub(start_timestamp),
+    ))
+}
+
+async fn epoch_start_timestamp(
+    db_connection: &Pool<Postgres>,
+    epoch: u64,
+) -> Result<Option<u64>, SqlxError> {
+    let Ok(epoch) = u16::try_from(epoch) else {
+        return Ok(None);
+    };
+    Ok(ClusterHistoryEntry::fetch_for_epoch(db_connection, epoch)
+        .await?
+        .map(|entry| entry.epoch_start_timestamp))
+}
+
+/// Compounds the exchange rate change over `elapsed_seconds` to a year, `None` when no time
+/// elapsed
+fn annualized_rate_change(start_rate: f64, end_rate: f64, elapsed_seconds: u64) -> Option<f64> {
+    if elapsed_seconds == 0 {
+        return None;
+    }
+    Some((end_rate / start_rate).powf(SECONDS_PER_YEAR / elapsed_seconds as f64) - 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_change_is_annualized_over_the_elapsed_time() {
+        // 1% over half a year
+        let apy = annualized_rate_change(1.0, 1.01, SECONDS_PER_YEAR as u64 / 2).unwrap();
+        assert!((apy - (1.01f64.powi(2) - 1.0)).abs() < 1e-12);
+        assert_eq!(annualized_rate_change(1.0, 1.01, 0), None);
+    }
+}
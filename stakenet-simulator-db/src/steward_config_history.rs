@@ -0,0 +1,189 @@
+use crate::big_decimal_u64::BigDecimalU64;
+use jito_steward::Parameters;
+use sqlx::{
+    Error as SqlxError, Pool, Postgres, postgres::PgQueryResult, prelude::FromRow,
+    types::BigDecimal,
+};
+
+/// Snapshot of the steward config parameters as they were on-chain during an epoch
+#[derive(FromRow, Clone, Debug)]
+pub struct StewardConfigHistory {
+    #[sqlx(try_from = "i32")]
+    pub epoch: u16,
+    #[sqlx(try_from = "i32")]
+    pub mev_commission_range: u16,
+    #[sqlx(try_from = "i32")]
+    pub epoch_credits_range: u16,
+    #[sqlx(try_from = "i32")]
+    pub commission_range: u16,
+    pub scoring_delinquency_threshold_ratio: f64,
+    pub instant_unstake_delinquency_threshold_ratio: f64,
+    #[sqlx(try_from = "i32")]
+    pub mev_commission_bps_threshold: u16,
+    #[sqlx(try_from = "i16")]
+    pub commission_threshold: u8,
+    #[sqlx(try_from = "i16")]
+    pub historical_commission_threshold: u8,
+    #[sqlx(try_from = "i16")]
+    pub priority_fee_lookback_epochs: u8,
+    #[sqlx(try_from = "i16")]
+    pub priority_fee_lookback_offset: u8,
+    #[sqlx(try_from = "i32")]
+    pub priority_fee_max_commission_bps: u16,
+    #[sqlx(try_from = "i32")]
+    pub priority_fee_error_margin_bps: u16,
+    #[sqlx(try_from = "i64")]
+    pub num_delegation_validators: u32,
+    #[sqlx(try_from = "i64")]
+    pub scoring_unstake_cap_bps: u32,
+    #[sqlx(try_from = "i64")]
+    pub instant_unstake_cap_bps: u32,
+    #[sqlx(try_from = "i64")]
+    pub stake_deposit_unstake_cap_bps: u32,
+    pub instant_unstake_epoch_progress: f64,
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub compute_score_slot_range: u64,
+    pub instant_unstake_inputs_epoch_progress: f64,
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub num_epochs_between_scoring: u64,
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub minimum_stake_lamports: u64,
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub minimum_voting_epochs: u64,
+    #[sqlx(try_from = "i32")]
+    pub priority_fee_scoring_start_epoch: u16,
+}
+
+impl StewardConfigHistory {
+    pub fn from_parameters(epoch: u16, parameters: &Parameters) -> Self {
+        Self {
+            epoch,
+            mev_commission_range: parameters.mev_commission_range,
+            epoch_credits_range: parameters.epoch_credits_range,
+            commission_range: parameters.commission_range,
+            scoring_delinquency_threshold_ratio: parameters.scoring_delinquency_threshold_ratio,
+            instant_unstake_delinquency_threshold_ratio: parameters
+                .instant_unstake_delinquency_threshold_ratio,
+            mev_commission_bps_threshold: parameters.mev_commission_bps_threshold,
+            commission_threshold: parameters.commission_threshold,
+            historical_commission_threshold: parameters.historical_commission_threshold,
+            priority_fee_lookback_epochs: parameters.priority_fee_lookback_epochs,
+            priority_fee_lookback_offset: parameters.priority_fee_lookback_offset,
+            priority_fee_max_commission_bps: parameters.priority_fee_max_commission_bps,
+            priority_fee_error_margin_bps: parameters.priority_fee_error_margin_bps,
+            num_delegation_validators: parameters.num_delegation_validators,
+            scoring_unstake_cap_bps: parameters.scoring_unstake_cap_bps,
+            instant_unstake_cap_bps: parameters.instant_unstake_cap_bps,
+            stake_deposit_unstake_cap_bps: parameters.stake_deposit_unstake_cap_bps,
+            instant_unstake_epoch_progress: parameters.instant_unstake_epoch_progress,
+            compute_score_slot_range: parameters.compute_score_slot_range,
+            instant_unstake_inputs_epoch_progress: parameters.instant_unstake_inputs_epoch_progress,
+            num_epochs_between_scoring: parameters.num_epochs_between_scoring,
+            minimum_stake_lamports: parameters.minimum_stake_lamports,
+            minimum_voting_epochs: parameters.minimum_voting_epochs,
+            priority_fee_scoring_start_epoch: parameters.priority_fee_scoring_start_epoch,
+        }
+    }
+
+    /// Overwrites `parameters` with the values recorded in this snapshot
+    pub fn apply_to(&self, parameters: &mut Parameters) {
+        parameters.mev_commission_range = self.mev_commission_range;
+        parameters.epoch_credits_range = self.epoch_credits_range;
+        parameters.commission_range = self.commission_range;
+        parameters.scoring_delinquency_threshold_ratio = self.scoring_delinquency_threshold_ratio;
+        parameters.instant_unstake_delinquency_threshold_ratio =
+            self.instant_unstake_delinquency_threshold_ratio;
+        parameters.mev_commission_bps_threshold = self.mev_commission_bps_threshold;
+        parameters.commission_threshold = self.commission_threshold;
+        parameters.historical_commission_threshold = self.historical_commission_threshold;
+        parameters.priority_fee_lookback_epochs = self.priority_fee_lookback_epochs;
+        parameters.priority_fee_lookback_offset = self.priority_fee_lookback_offset;
+        parameters.priority_fee_max_commission_bps = self.priority_fee_max_commission_bps;
+        parameters.priority_fee_error_margin_bps = self.priority_fee_error_margin_bps;
+        parameters.num_delegation_validators = self.num_delegation_validators;
+        parameters.scoring_unstake_cap_bps = self.scoring_unstake_cap_bps;
+        parameters.instant_unstake_cap_bps = self.instant_unstake_cap_bps;
+        parameters.stake_deposit_unstake_cap_bps = self.stake_deposit_unstake_cap_bps;
+        parameters.instant_unstake_epoch_progress = self.instant_unstake_epoch_progress;
+        parameters.compute_score_slot_range = self.compute_score_slot_range;
+        parameters.instant_unstake_inputs_epoch_progress =
+            self.instant_unstake_inputs_epoch_progress;
+        parameters.num_epochs_between_scoring = self.num_epochs_between_scoring;
+        parameters.minimum_stake_lamports = self.minimum_stake_lamports;
+        parameters.minimum_voting_epochs = self.minimum_voting_epochs;
+        parameters.priority_fee_scoring_start_epoch = self.priority_fee_scoring_start_epoch;
+    }
+
+    pub async fn upsert(
+        db_connection: &Pool<Postgres>,
+        record: Self,
+    ) -> Result<PgQueryResult, SqlxError> {
+        let sql = "
+    INSERT INTO steward_config_history (epoch,mev_commission_range,epoch_credits_range,commission_range,scoring_delinquency_threshold_ratio,instant_unstake_delinquency_threshold_ratio,mev_commission_bps_threshold,commission_threshold,historical_commission_threshold,priority_fee_lookback_epochs,priority_fee_lookback_offset,priority_fee_max_commission_bps,priority_fee_error_margin_bps,num_delegation_validators,scoring_unstake_cap_bps,instant_unstake_cap_bps,stake_deposit_unstake_cap_bps,instant_unstake_epoch_progress,compute_score_slot_range,instant_unstake_inputs_epoch_progress,num_epochs_between_scoring,minimum_stake_lamports,minimum_voting_epochs,priority_fee_scoring_start_epoch) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24) \
+    ON CONFLICT (epoch) DO UPDATE SET \
+    mev_commission_range = EXCLUDED.mev_commission_range,
+    epoch_credits_range = EXCLUDED.epoch_credits_range,
+    commission_range = EXCLUDED.commission_range,
+    scoring_delinquency_threshold_ratio = EXCLUDED.scoring_delinquency_threshold_ratio,
+    instant_unstake_delinquency_threshold_ratio = EXCLUDED.instant_unstake_delinquency_threshold_ratio,
+    mev_commission_bps_threshold = EXCLUDED.mev_commission_bps_threshold,
+    commission_threshold = EXCLUDED.commission_threshold,
+    historical_commission_threshold = EXCLUDED.historical_commission_threshold,
+    priority_fee_lookback_epochs = EXCLUDED.priority_fee_lookback_epochs,
+    priority_fee_lookback_offset = EXCLUDED.priority_fee_lookback_offset,
+    priority_fee_max_commission_bps = EXCLUDED.priority_fee_max_commission_bps,
+    priority_fee_error_margin_bps = EXCLUDED.priority_fee_error_margin_bps,
+    num_delegation_validators = EXCLUDED.num_delegation_validators,
+    scoring_unstake_cap_bps = EXCLUDED.scoring_unstake_cap_bps,
+    instant_unstake_cap_bps = EXCLUDED.instant_unstake_cap_bps,
+    stake_deposit_unstake_cap_bps = EXCLUDED.stake_deposit_unstake_cap_bps,
+    instant_unstake_epoch_progress = EXCLUDED.instant_unstake_epoch_progress,
+    compute_score_slot_range = EXCLUDED.compute_score_slot_range,
+    instant_unstake_inputs_epoch_progress = EXCLUDED.instant_unstake_inputs_epoch_progress,
+    num_epochs_between_scoring = EXCLUDED.num_epochs_between_scoring,
+    minimum_stake_lamports = EXCLUDED.minimum_stake_lamports,
+    minimum_voting_epochs = EXCLUDED.minimum_voting_epochs,
+    priority_fee_scoring_start_epoch = EXCLUDED.priority_fee_scoring_start_epoch
+    ";
+        sqlx::query(sql)
+            .bind(i32::from(record.epoch))
+            .bind(i32::from(record.mev_commission_range))
+            .bind(i32::from(record.epoch_credits_range))
+            .bind(i32::from(record.commission_range))
+            .bind(record.scoring_delinquency_threshold_ratio)
+            .bind(record.instant_unstake_delinquency_threshold_ratio)
+            .bind(i32::from(record.mev_commission_bps_threshold))
+            .bind(i16::from(record.commission_threshold))
+            .bind(i16::from(record.historical_commission_threshold))
+            .bind(i16::from(record.priority_fee_lookback_epochs))
+            .bind(i16::from(record.priority_fee_lookback_offset))
+            .bind(i32::from(record.priority_fee_max_commission_bps))
+            .bind(i32::from(record.priority_fee_error_margin_bps))
+            .bind(i64::from(record.num_delegation_validators))
+            .bind(i64::from(record.scoring_unstake_cap_bps))
+            .bind(i64::from(record.instant_unstake_cap_bps))
+            .bind(i64::from(record.stake_deposit_unstake_cap_bps))
+            .bind(record.instant_unstake_epoch_progress)
+            .bind(BigDecimal::from(record.compute_score_slot_range))
+            .bind(record.instant_unstake_inputs_epoch_progress)
+            .bind(BigDecimal::from(record.num_epochs_between_scoring))
+            .bind(BigDecimal::from(record.minimum_stake_lamports))
+            .bind(BigDecimal::from(record.minimum_voting_epochs))
+            .bind(i32::from(record.priority_fee_scoring_start_epoch))
+            .execute(db_connection)
+            .await
+    }
+
+    /// Returns every recorded snapshot up to `end_epoch`, ordered by epoch
+    pub async fn fetch_up_to_epoch(
+        db_connection: &Pool<Postgres>,
+        end_epoch: u16,
+    ) -> Result<Vec<Self>, SqlxError> {
+        sqlx::query_as::<_, Self>(
+            "SELECT * FROM steward_config_history WHERE epoch <= $1 ORDER BY epoch ASC",
+        )
+        .bind(i32::from(end_epoch))
+        .fetch_all(db_connection)
+        .await
+    }
+}
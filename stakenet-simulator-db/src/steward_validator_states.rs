@@ -0,0 +1,150 @@
+use crate::big_decimal_u64::BigDecimalU64;
+use sqlx::{Error, FromRow, Pool, Postgres, QueryBuilder, types::BigDecimal};
+
+/// The steward program stores scores as `score * SCORE_SCALE` in a `u32`
+pub const SCORE_SCALE: f64 = 1_000_000_000.0;
+
+/// A validator's entry in the on-chain steward state account at an epoch
+#[derive(FromRow, Clone, Debug)]
+pub struct StewardValidatorState {
+    pub id: String, // {epoch}-{vote_pubkey}
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub epoch: u64,
+    /// Epoch the scores were computed in, i.e. the start of the steward cycle
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub cycle_start_epoch: u64,
+    pub vote_pubkey: String,
+    /// Index of the validator in the stake pool's validator list
+    #[sqlx(try_from = "i64")]
+    pub validator_index: u32,
+    #[sqlx(try_from = "i64")]
+    pub score: u32,
+    #[sqlx(try_from = "i64")]
+    pub yield_score: u32,
+    #[sqlx(try_from = "i64")]
+    pub delegation_numerator: u32,
+    #[sqlx(try_from = "i64")]
+    pub delegation_denominator: u32,
+    pub instant_unstake: bool,
+    pub progress: bool,
+}
+
+impl StewardValidatorState {
+    const NUM_FIELDS: u8 = 11;
+    // Based on the bind limit of postgres
+    const INSERT_CHUNK_SIZE: usize = 65534 / Self::NUM_FIELDS as usize;
+    const INSERT_QUERY: &str = "INSERT INTO steward_validator_states \
+        (id, epoch, cycle_start_epoch, vote_pubkey, validator_index, score, yield_score, delegation_numerator, delegation_denominator, instant_unstake, progress) VALUES ";
+    const ON_CONFLICT: &str = " ON CONFLICT (id) DO UPDATE SET \
+        cycle_start_epoch = EXCLUDED.cycle_start_epoch, \
+        validator_index = EXCLUDED.validator_index, \
+        score = EXCLUDED.score, \
+        yield_score = EXCLUDED.yield_score, \
+        delegation_numerator = EXCLUDED.delegation_numerator, \
+        delegation_denominator = EXCLUDED.delegation_denominator, \
+        instant_unstake = EXCLUDED.instant_unstake, \
+        progress = EXCLUDED.progress";
+
+    pub fn new(
+        epoch: u64,
+        cycle_start_epoch: u64,
+        vote_pubkey: String,
+        validator_index: u32,
+        score: u32,
+        yield_score: u32,
+        delegation_numerator: u32,
+        delegation_denominator: u32,
+        instant_unstake: bool,
+        progress: bool,
+    ) -> Self {
+        let id = format!("{}-{}", epoch, vote_pubkey);
+        Self {
+            id,
+            epoch,
+            cycle_start_epoch,
+            vote_pubkey,
+            validator_index,
+            score,
+            yield_score,
+            delegation_numerator,
+            delegation_denominator,
+            instant_unstake,
+            progress,
+        }
+    }
+
+    /// Score as computed by `validator_score`
+    pub fn score(&self) -> f64 {
+        self.score as f64 / SCORE_SCALE
+    }
+
+    /// Yield score as computed by `validator_score`
+    pub fn yield_score(&self) -> f64 {
+        self.yield_score as f64 / SCORE_SCALE
+    }
+
+    pub async fn bulk_insert(
+        db_connection: &Pool<Postgres>,
+        records: Vec<Self>,
+    ) -> Result<(), Error> {
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        let mut query_builder: QueryBuilder<Postgres> = QueryBuilder::new(Self::INSERT_QUERY);
+        let mut num_records: usize = 0;
+
+        for record in records.into_iter() {
+            num_records += 1;
+            if num_records > 1 {
+                query_builder.push(", (");
+            } else {
+                query_builder.push("(");
+            }
+
+            let mut separated = query_builder.separated(", ");
+            separated.push_bind(record.id);
+            separated.push_bind(BigDecimal::from(record.epoch));
+            separated.push_bind(BigDecimal::from(record.cycle_start_epoch));
+            separated.push_bind(record.vote_pubkey);
+            separated.push_bind(i64::from(record.validator_index));
+            separated.push_bind(i64::from(record.score));
+            separated.push_bind(i64::from(record.yield_score));
+            separated.push_bind(i64::from(record.delegation_numerator));
+            separated.push_bind(i64::from(record.delegation_denominator));
+            separated.push_bind(record.instant_unstake);
+            separated.push_bind(record.progress);
+            separated.push_unseparated(") ");
+
+            if num_records >= Self::INSERT_CHUNK_SIZE {
+                query_builder.push(Self::ON_CONFLICT);
+                let query = query_builder.build();
+                query.execute(db_connection).await?;
+                num_records = 0;
+                query_builder = QueryBuilder::new(Self::INSERT_QUERY);
+            }
+        }
+
+        if num_records > 0 {
+            query_builder.push(Self::ON_CONFLICT);
+            let query = query_builder.build();
+            query.execute(db_connection).await?;
+        }
+        Ok(())
+    }
+
+    /// Returns the scores computed at the start of the cycle beginning at `cycle_start_epoch`,
+    /// taken from the latest epoch recorded during that cycle
+    pub async fn fetch_for_cycle_start_epoch(
+        db_connection: &Pool<Postgres>,
+        cycle_start_epoch: u64,
+    ) -> Result<Vec<Self>, Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT DISTINCT ON (vote_pubkey) * FROM steward_validator_states \
+            WHERE cycle_start_epoch = $1 ORDER BY vote_pubkey, epoch DESC",
+        )
+        .bind(BigDecimal::from(cycle_start_epoch))
+        .fetch_all(db_connection)
+        .await
+    }
+}
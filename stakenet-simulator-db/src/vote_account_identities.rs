@@ -0,0 +1,171 @@
+use crate::big_decimal_u64::BigDecimalU64;
+use sqlx::{Error, FromRow, Pool, Postgres, QueryBuilder, types::BigDecimal};
+use std::collections::HashMap;
+
+/// The node identity a vote account voted for in an epoch
+#[derive(Clone, Debug, FromRow)]
+pub struct VoteAccountIdentity {
+    pub id: String, // {epoch}-{vote_pubkey}
+    pub vote_pubkey: String,
+    pub identity_pubkey: String,
+    #[sqlx(try_from = "BigDecimalU64")]
+    pub epoch: u64,
+}
+
+impl VoteAccountIdentity {
+    const NUM_FIELDS: u8 = 4;
+    // Based on the bind limit of postgres
+    const INSERT_CHUNK_SIZE: usize = 65534 / Self::NUM_FIELDS as usize;
+    const INSERT_QUERY: &str =
+        "INSERT INTO vote_account_identities (id, vote_pubkey, identity_pubkey, epoch) VALUES ";
+    const ON_CONFLICT: &str =
+        " ON CONFLICT (id) DO UPDATE SET identity_pubkey = EXCLUDED.identity_pubkey";
+
+    pub fn new(vote_pubkey: String, identity_pubkey: String, epoch: u64) -> Self {
+        Self {
+            id: format!("{}-{}", epoch, vote_pubkey),
+            vote_pubkey,
+            identity_pubkey,
+            epoch,
+        }
+    }
+
+    pub async fn bulk_insert(
+        db_connection: &Pool<Postgres>,
+        records: Vec<Self>,
+    ) -> Result<(), Error> {
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        let mut query_builder: QueryBuilder<Postgres> = QueryBuilder::new(Self::INSERT_QUERY);
+        let mut num_records: usize = 0;
+
+        for record in records.into_iter() {
+            num_records += 1;
+            if num_records > 1 {
+                query_builder.push(", (");
+            } else {
+                query_builder.push("(");
+            }
+
+            let mut separated = query_builder.separated(", ");
+            separated.push_bind(record.id);
+            separated.push_bind(record.vote_pubkey);
+            separated.push_bind(record.identity_pubkey);
+            separated.push_bind(BigDecimal::from(record.epoch));
+            separated.push_unseparated(") ");
+
+            if num_records >= Self::INSERT_CHUNK_SIZE {
+                query_builder.push(Self::ON_CONFLICT);
+                let query = query_builder.build();
+                query.execute(db_connection).await?;
+                num_records = 0;
+                query_builder = QueryBuilder::new(Self::INSERT_QUERY);
+            }
+        }
+
+        if num_records > 0 {
+            query_builder.push(Self::ON_CONFLICT);
+            let query = query_builder.build();
+            query.execute(db_connection).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn fetch_for_epoch_range(
+        db_connection: &Pool<Postgres>,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<Self>, Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT * FROM vote_account_identities WHERE epoch BETWEEN $1 AND $2",
+        )
+        .bind(BigDecimal::from(start_epoch))
+        .bind(BigDecimal::from(end_epoch))
+        .fetch_all(db_connection)
+        .await
+    }
+
+    pub async fn fetch_for_vote_accounts_and_epochs(
+        db_connection: &Pool<Postgres>,
+        vote_accounts: &Vec<String>,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<Self>, Error> {
+        sqlx::query_as::<_, Self>(
+            "SELECT * FROM vote_account_identities \
+            WHERE vote_pubkey = ANY($1) AND epoch BETWEEN $2 AND $3",
+        )
+        .bind(vote_accounts)
+        .bind(BigDecimal::from(start_epoch))
+        .bind(BigDecimal::from(end_epoch))
+        .fetch_all(db_connection)
+        .await
+    }
+}
+
+/// Pairs every record keyed by `(identity_pubkey, epoch)` with the vote accounts that voted for
+/// the identity in that epoch. Records without a recorded vote account are left out.
+pub fn join_on_identity<'a, T>(
+    identities: &[VoteAccountIdentity],
+    records: &'a [T],
+    key: impl Fn(&T) -> (&str, u64),
+) -> Vec<(String, &'a T)> {
+    let mut vote_accounts: HashMap<(&str, u64), Vec<&str>> = HashMap::new();
+    for identity in identities {
+        vote_accounts
+            .entry((identity.identity_pubkey.as_str(), identity.epoch))
+            .or_default()
+            .push(identity.vote_pubkey.as_str());
+    }
+
+    records
+        .iter()
+        .flat_map(|record| {
+            vote_accounts
+                .get(&key(record))
+                .into_iter()
+                .flatten()
+                .map(move |vote_pubkey| (vote_pubkey.to_string(), record))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_records_join_on_the_identity_of_their_epoch() {
+        let identities = [
+            VoteAccountIdentity::new("vote-a".to_string(), "identity-1".to_string(), 800),
+            // The vote account switched identity
+            VoteAccountIdentity::new("vote-a".to_string(), "identity-2".to_string(), 801),
+            VoteAccountIdentity::new("vote-b".to_string(), "identity-1".to_string(), 801),
+        ];
+        let records = [
+            ("identity-1", 800, 10),
+            ("identity-1", 801, 20),
+            ("identity-2", 801, 30),
+            // No vote account recorded the identity this epoch
+            ("identity-2", 800, 40),
+        ];
+
+        let joined: Vec<(String, u64)> =
+            join_on_identity(&identities, &records, |(identity, epoch, _)| {
+                (*identity, *epoch)
+            })
+            .into_iter()
+            .map(|(vote_pubkey, (_, _, value))| (vote_pubkey, *value))
+            .collect();
+        assert_eq!(
+            joined,
+            vec![
+                ("vote-a".to_string(), 10),
+                ("vote-b".to_string(), 20),
+                ("vote-a".to_string(), 30),
+            ]
+        );
+    }
+}
@@ -0,0 +1,67 @@
+create table "public"."epoch_schedules" (
+    "id" integer not null,
+    "slots_per_epoch" u_64 not null,
+    "leader_schedule_slot_offset" u_64 not null,
+    "warmup" boolean not null,
+    "first_normal_epoch" u_64 not null,
+    "first_normal_slot" u_64 not null
+);
+
+
+alter table "public"."epoch_schedules" enable row level security;
+
+CREATE UNIQUE INDEX epoch_schedules_pkey ON public.epoch_schedules USING btree (id);
+
+alter table "public"."epoch_schedules" add constraint "epoch_schedules_pkey" PRIMARY KEY using index "epoch_schedules_pkey";
+
+grant delete on table "public"."epoch_schedules" to "anon";
+
+grant insert on table "public"."epoch_schedules" to "anon";
+
+grant references on table "public"."epoch_schedules" to "anon";
+
+grant select on table "public"."epoch_schedules" to "anon";
+
+grant trigger on table "public"."epoch_schedules" to "anon";
+
+grant truncate on table "public"."epoch_schedules" to "anon";
+
+grant update on table "public"."epoch_schedules" to "anon";
+
+grant delete on table "public"."epoch_schedules" to "authenticated";
+
+grant insert on table "public"."epoch_schedules" to "authenticated";
+
+grant references on table "public"."epoch_schedules" to "authenticated";
+
+grant select on table "public"."epoch_schedules" to "authenticated";
+
+grant trigger on table "public"."epoch_schedules" to "authenticated";
+
+grant truncate on table "public"."epoch_schedules" to "authenticated";
+
+grant update on table "public"."epoch_schedules" to "authenticated";
+
+grant delete on table "public"."epoch_schedules" to "service_role";
+
+grant insert on table "public"."epoch_schedules" to "service_role";
+
+grant references on table "public"."epoch_schedules" to "service_role";
+
+grant select on table "public"."epoch_schedules" to "service_role";
+
+grant trigger on table "public"."epoch_schedules" to "service_role";
+
+grant truncate on table "public"."epoch_schedules" to "service_role";
+
+grant update on table "public"."epoch_schedules" to "service_role";
+
+create policy "Enable read access for all users"
+on "public"."epoch_schedules"
+as permissive
+for select
+to public
+using (true);
+
+
+
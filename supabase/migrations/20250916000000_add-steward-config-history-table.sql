@@ -0,0 +1,85 @@
+create table "public"."steward_config_history" (
+    "epoch" integer not null,
+    "mev_commission_range" integer not null,
+    "epoch_credits_range" integer not null,
+    "commission_range" integer not null,
+    "scoring_delinquency_threshold_ratio" double precision not null,
+    "instant_unstake_delinquency_threshold_ratio" double precision not null,
+    "mev_commission_bps_threshold" integer not null,
+    "commission_threshold" smallint not null,
+    "historical_commission_threshold" smallint not null,
+    "priority_fee_lookback_epochs" smallint not null,
+    "priority_fee_lookback_offset" smallint not null,
+    "priority_fee_max_commission_bps" integer not null,
+    "priority_fee_error_margin_bps" integer not null,
+    "num_delegation_validators" bigint not null,
+    "scoring_unstake_cap_bps" bigint not null,
+    "instant_unstake_cap_bps" bigint not null,
+    "stake_deposit_unstake_cap_bps" bigint not null,
+    "instant_unstake_epoch_progress" double precision not null,
+    "compute_score_slot_range" u_64 not null,
+    "instant_unstake_inputs_epoch_progress" double precision not null,
+    "num_epochs_between_scoring" u_64 not null,
+    "minimum_stake_lamports" u_64 not null,
+    "minimum_voting_epochs" u_64 not null,
+    "priority_fee_scoring_start_epoch" integer not null
+);
+
+
+alter table "public"."steward_config_history" enable row level security;
+
+CREATE UNIQUE INDEX steward_config_history_pkey ON public.steward_config_history USING btree (epoch);
+
+alter table "public"."steward_config_history" add constraint "steward_config_history_pkey" PRIMARY KEY using index "steward_config_history_pkey";
+
+grant delete on table "public"."steward_config_history" to "anon";
+
+grant insert on table "public"."steward_config_history" to "anon";
+
+grant references on table "public"."steward_config_history" to "anon";
+
+grant select on table "public"."steward_config_history" to "anon";
+
+grant trigger on table "public"."steward_config_history" to "anon";
+
+grant truncate on table "public"."steward_config_history" to "anon";
+
+grant update on table "public"."steward_config_history" to "anon";
+
+grant delete on table "public"."steward_config_history" to "authenticated";
+
+grant insert on table "public"."steward_config_history" to "authenticated";
+
+grant references on table "public"."steward_config_history" to "authenticated";
+
+grant select on table "public"."steward_config_history" to "authenticated";
+
+grant trigger on table "public"."steward_config_history" to "authenticated";
+
+grant truncate on table "public"."steward_config_history" to "authenticated";
+
+grant update on table "public"."steward_config_history" to "authenticated";
+
+grant delete on table "public"."steward_config_history" to "service_role";
+
+grant insert on table "public"."steward_config_history" to "service_role";
+
+grant references on table "public"."steward_config_history" to "service_role";
+
+grant select on table "public"."steward_config_history" to "service_role";
+
+grant trigger on table "public"."steward_config_history" to "service_role";
+
+grant truncate on table "public"."steward_config_history" to "service_role";
+
+grant update on table "public"."steward_config_history" to "service_role";
+
+create policy "Enable read access for all users"
+on "public"."steward_config_history"
+as permissive
+for select
+to public
+using (true);
+
+
+
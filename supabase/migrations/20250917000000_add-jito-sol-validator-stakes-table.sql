@@ -0,0 +1,66 @@
+create table "public"."jito_sol_validator_stakes" (
+    "id" character varying(70) not null,
+    "epoch" u_64 not null,
+    "vote_pubkey" solana_pubkey not null,
+    "active_stake_lamports" u_64 not null,
+    "transient_stake_lamports" u_64 not null
+);
+
+
+alter table "public"."jito_sol_validator_stakes" enable row level security;
+
+CREATE UNIQUE INDEX jito_sol_validator_stakes_pkey ON public.jito_sol_validator_stakes USING btree (id);
+
+alter table "public"."jito_sol_validator_stakes" add constraint "jito_sol_validator_stakes_pkey" PRIMARY KEY using index "jito_sol_validator_stakes_pkey";
+
+grant delete on table "public"."jito_sol_validator_stakes" to "anon";
+
+grant insert on table "public"."jito_sol_validator_stakes" to "anon";
+
+grant references on table "public"."jito_sol_validator_stakes" to "anon";
+
+grant select on table "public"."jito_sol_validator_stakes" to "anon";
+
+grant trigger on table "public"."jito_sol_validator_stakes" to "anon";
+
+grant truncate on table "public"."jito_sol_validator_stakes" to "anon";
+
+grant update on table "public"."jito_sol_validator_stakes" to "anon";
+
+grant delete on table "public"."jito_sol_validator_stakes" to "authenticated";
+
+grant insert on table "public"."jito_sol_validator_stakes" to "authenticated";
+
+grant references on table "public"."jito_sol_validator_stakes" to "authenticated";
+
+grant select on table "public"."jito_sol_validator_stakes" to "authenticated";
+
+grant trigger on table "public"."jito_sol_validator_stakes" to "authenticated";
+
+grant truncate on table "public"."jito_sol_validator_stakes" to "authenticated";
+
+grant update on table "public"."jito_sol_validator_stakes" to "authenticated";
+
+grant delete on table "public"."jito_sol_validator_stakes" to "service_role";
+
+grant insert on table "public"."jito_sol_validator_stakes" to "service_role";
+
+grant references on table "public"."jito_sol_validator_stakes" to "service_role";
+
+grant select on table "public"."jito_sol_validator_stakes" to "service_role";
+
+grant trigger on table "public"."jito_sol_validator_stakes" to "service_role";
+
+grant truncate on table "public"."jito_sol_validator_stakes" to "service_role";
+
+grant update on table "public"."jito_sol_validator_stakes" to "service_role";
+
+create policy "Enable read access for all users"
+on "public"."jito_sol_validator_stakes"
+as permissive
+for select
+to public
+using (true);
+
+
+
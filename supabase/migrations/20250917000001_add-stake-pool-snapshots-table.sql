@@ -0,0 +1,77 @@
+create table "public"."stake_pool_snapshots" (
+    "epoch" u_64 not null,
+    "total_lamports" u_64 not null,
+    "pool_token_supply" u_64 not null,
+    "reserve_lamports" u_64 not null,
+    "epoch_fee_numerator" u_64 not null,
+    "epoch_fee_denominator" u_64 not null,
+    "stake_deposit_fee_numerator" u_64 not null,
+    "stake_deposit_fee_denominator" u_64 not null,
+    "stake_withdrawal_fee_numerator" u_64 not null,
+    "stake_withdrawal_fee_denominator" u_64 not null,
+    "sol_deposit_fee_numerator" u_64 not null,
+    "sol_deposit_fee_denominator" u_64 not null,
+    "sol_withdrawal_fee_numerator" u_64 not null,
+    "sol_withdrawal_fee_denominator" u_64 not null,
+    "stake_referral_fee" smallint not null,
+    "sol_referral_fee" smallint not null
+);
+
+
+alter table "public"."stake_pool_snapshots" enable row level security;
+
+CREATE UNIQUE INDEX stake_pool_snapshots_pkey ON public.stake_pool_snapshots USING btree (epoch);
+
+alter table "public"."stake_pool_snapshots" add constraint "stake_pool_snapshots_pkey" PRIMARY KEY using index "stake_pool_snapshots_pkey";
+
+grant delete on table "public"."stake_pool_snapshots" to "anon";
+
+grant insert on table "public"."stake_pool_snapshots" to "anon";
+
+grant references on table "public"."stake_pool_snapshots" to "anon";
+
+grant select on table "public"."stake_pool_snapshots" to "anon";
+
+grant trigger on table "public"."stake_pool_snapshots" to "anon";
+
+grant truncate on table "public"."stake_pool_snapshots" to "anon";
+
+grant update on table "public"."stake_pool_snapshots" to "anon";
+
+grant delete on table "public"."stake_pool_snapshots" to "authenticated";
+
+grant insert on table "public"."stake_pool_snapshots" to "authenticated";
+
+grant references on table "public"."stake_pool_snapshots" to "authenticated";
+
+grant select on table "public"."stake_pool_snapshots" to "authenticated";
+
+grant trigger on table "public"."stake_pool_snapshots" to "authenticated";
+
+grant truncate on table "public"."stake_pool_snapshots" to "authenticated";
+
+grant update on table "public"."stake_pool_snapshots" to "authenticated";
+
+grant delete on table "public"."stake_pool_snapshots" to "service_role";
+
+grant insert on table "public"."stake_pool_snapshots" to "service_role";
+
+grant references on table "public"."stake_pool_snapshots" to "service_role";
+
+grant select on table "public"."stake_pool_snapshots" to "service_role";
+
+grant trigger on table "public"."stake_pool_snapshots" to "service_role";
+
+grant truncate on table "public"."stake_pool_snapshots" to "service_role";
+
+grant update on table "public"."stake_pool_snapshots" to "service_role";
+
+create policy "Enable read access for all users"
+on "public"."stake_pool_snapshots"
+as permissive
+for select
+to public
+using (true);
+
+
+
@@ -0,0 +1,72 @@
+create table "public"."steward_validator_states" (
+    "id" character varying(70) not null,
+    "epoch" u_64 not null,
+    "cycle_start_epoch" u_64 not null,
+    "vote_pubkey" solana_pubkey not null,
+    "validator_index" bigint not null,
+    "score" bigint not null,
+    "yield_score" bigint not null,
+    "delegation_numerator" bigint not null,
+    "delegation_denominator" bigint not null,
+    "instant_unstake" boolean not null,
+    "progress" boolean not null
+);
+
+
+alter table "public"."steward_validator_states" enable row level security;
+
+CREATE UNIQUE INDEX steward_validator_states_pkey ON public.steward_validator_states USING btree (id);
+
+alter table "public"."steward_validator_states" add constraint "steward_validator_states_pkey" PRIMARY KEY using index "steward_validator_states_pkey";
+
+grant delete on table "public"."steward_validator_states" to "anon";
+
+grant insert on table "public"."steward_validator_states" to "anon";
+
+grant references on table "public"."steward_validator_states" to "anon";
+
+grant select on table "public"."steward_validator_states" to "anon";
+
+grant trigger on table "public"."steward_validator_states" to "anon";
+
+grant truncate on table "public"."steward_validator_states" to "anon";
+
+grant update on table "public"."steward_validator_states" to "anon";
+
+grant delete on table "public"."steward_validator_states" to "authenticated";
+
+grant insert on table "public"."steward_validator_states" to "authenticated";
+
+grant references on table "public"."steward_validator_states" to "authenticated";
+
+grant select on table "public"."steward_validator_states" to "authenticated";
+
+grant trigger on table "public"."steward_validator_states" to "authenticated";
+
+grant truncate on table "public"."steward_validator_states" to "authenticated";
+
+grant update on table "public"."steward_validator_states" to "authenticated";
+
+grant delete on table "public"."steward_validator_states" to "service_role";
+
+grant insert on table "public"."steward_validator_states" to "service_role";
+
+grant references on table "public"."steward_validator_states" to "service_role";
+
+grant select on table "public"."steward_validator_states" to "service_role";
+
+grant trigger on table "public"."steward_validator_states" to "service_role";
+
+grant truncate on table "public"."steward_validator_states" to "service_role";
+
+grant update on table "public"."steward_validator_states" to "service_role";
+
+create policy "Enable read access for all users"
+on "public"."steward_validator_states"
+as permissive
+for select
+to public
+using (true);
+
+
+
@@ -0,0 +1,70 @@
+create table "public"."epoch_block_production" (
+    "id" character varying(70) not null,
+    "identity_pubkey" solana_pubkey not null,
+    "epoch" u_64 not null,
+    "leader_slots" bigint not null,
+    "blocks_produced" bigint not null,
+    "skipped_slots" bigint not null,
+    "rpc_missing_slots" bigint not null
+);
+
+
+alter table "public"."epoch_block_production" enable row level security;
+
+CREATE UNIQUE INDEX epoch_block_production_pkey ON public.epoch_block_production USING btree (id);
+
+CREATE INDEX idx_epoch_block_production_by_identity_and_epoch ON public.epoch_block_production USING btree (identity_pubkey, epoch);
+
+alter table "public"."epoch_block_production" add constraint "epoch_block_production_pkey" PRIMARY KEY using index "epoch_block_production_pkey";
+
+grant delete on table "public"."epoch_block_production" to "anon";
+
+grant insert on table "public"."epoch_block_production" to "anon";
+
+grant references on table "public"."epoch_block_production" to "anon";
+
+grant select on table "public"."epoch_block_production" to "anon";
+
+grant trigger on table "public"."epoch_block_production" to "anon";
+
+grant truncate on table "public"."epoch_block_production" to "anon";
+
+grant update on table "public"."epoch_block_production" to "anon";
+
+grant delete on table "public"."epoch_block_production" to "authenticated";
+
+grant insert on table "public"."epoch_block_production" to "authenticated";
+
+grant references on table "public"."epoch_block_production" to "authenticated";
+
+grant select on table "public"."epoch_block_production" to "authenticated";
+
+grant trigger on table "public"."epoch_block_production" to "authenticated";
+
+grant truncate on table "public"."epoch_block_production" to "authenticated";
+
+grant update on table "public"."epoch_block_production" to "authenticated";
+
+grant delete on table "public"."epoch_block_production" to "service_role";
+
+grant insert on table "public"."epoch_block_production" to "service_role";
+
+grant references on table "public"."epoch_block_production" to "service_role";
+
+grant select on table "public"."epoch_block_production" to "service_role";
+
+grant trigger on table "public"."epoch_block_production" to "service_role";
+
+grant truncate on table "public"."epoch_block_production" to "service_role";
+
+grant update on table "public"."epoch_block_production" to "service_role";
+
+create policy "Enable read access for all users"
+on "public"."epoch_block_production"
+as permissive
+for select
+to public
+using (true);
+
+
+
@@ -0,0 +1,67 @@
+create table "public"."vote_account_identities" (
+    "id" character varying(70) not null,
+    "vote_pubkey" solana_pubkey not null,
+    "identity_pubkey" solana_pubkey not null,
+    "epoch" u_64 not null
+);
+
+
+alter table "public"."vote_account_identities" enable row level security;
+
+CREATE UNIQUE INDEX vote_account_identities_pkey ON public.vote_account_identities USING btree (id);
+
+CREATE INDEX idx_vote_account_identities_by_identity_and_epoch ON public.vote_account_identities USING btree (identity_pubkey, epoch);
+
+alter table "public"."vote_account_identities" add constraint "vote_account_identities_pkey" PRIMARY KEY using index "vote_account_identities_pkey";
+
+grant delete on table "public"."vote_account_identities" to "anon";
+
+grant insert on table "public"."vote_account_identities" to "anon";
+
+grant references on table "public"."vote_account_identities" to "anon";
+
+grant select on table "public"."vote_account_identities" to "anon";
+
+grant trigger on table "public"."vote_account_identities" to "anon";
+
+grant truncate on table "public"."vote_account_identities" to "anon";
+
+grant update on table "public"."vote_account_identities" to "anon";
+
+grant delete on table "public"."vote_account_identities" to "authenticated";
+
+grant insert on table "public"."vote_account_identities" to "authenticated";
+
+grant references on table "public"."vote_account_identities" to "authenticated";
+
+grant select on table "public"."vote_account_identities" to "authenticated";
+
+grant trigger on table "public"."vote_account_identities" to "authenticated";
+
+grant truncate on table "public"."vote_account_identities" to "authenticated";
+
+grant update on table "public"."vote_account_identities" to "authenticated";
+
+grant delete on table "public"."vote_account_identities" to "service_role";
+
+grant insert on table "public"."vote_account_identities" to "service_role";
+
+grant references on table "public"."vote_account_identities" to "service_role";
+
+grant select on table "public"."vote_account_identities" to "service_role";
+
+grant trigger on table "public"."vote_account_identities" to "service_role";
+
+grant truncate on table "public"."vote_account_identities" to "service_role";
+
+grant update on table "public"."vote_account_identities" to "service_role";
+
+create policy "Enable read access for all users"
+on "public"."vote_account_identities"
+as permissive
+for select
+to public
+using (true);
+
+
+
@@ -0,0 +1,23 @@
+--
+-- Epoch Schedule Table
+--
+-- Stores the cluster's EpochSchedule sysvar, used for epoch to slot conversions
+--
+CREATE TABLE IF NOT EXISTS "public"."epoch_schedules"(
+    "id" INTEGER NOT NULL PRIMARY KEY,
+    "slots_per_epoch" "public"."u_64" NOT NULL,
+    "leader_schedule_slot_offset" "public"."u_64" NOT NULL,
+    "warmup" BOOLEAN NOT NULL,
+    "first_normal_epoch" "public"."u_64" NOT NULL,
+    "first_normal_slot" "public"."u_64" NOT NULL
+);
+
+--
+-- Row Level Security Policies
+--
+ALTER TABLE "public"."epoch_schedules" ENABLE ROW LEVEL SECURITY;
+
+-- Policy: Enable read access for all users
+CREATE POLICY "Enable read access for all users" ON "public"."epoch_schedules"
+    FOR SELECT
+        USING (TRUE);
\ No newline at end of file
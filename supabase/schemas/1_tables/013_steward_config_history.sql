@@ -0,0 +1,41 @@
+--
+-- Steward Config History Table
+--
+-- Stores the steward config parameters recorded at each epoch
+--
+CREATE TABLE IF NOT EXISTS "public"."steward_config_history"(
+    "epoch" INTEGER NOT NULL PRIMARY KEY,
+    "mev_commission_range" INTEGER NOT NULL,
+    "epoch_credits_range" INTEGER NOT NULL,
+    "commission_range" INTEGER NOT NULL,
+    "scoring_delinquency_threshold_ratio" DOUBLE PRECISION NOT NULL,
+    "instant_unstake_delinquency_threshold_ratio" DOUBLE PRECISION NOT NULL,
+    "mev_commission_bps_threshold" INTEGER NOT NULL,
+    "commission_threshold" SMALLINT NOT NULL,
+    "historical_commission_threshold" SMALLINT NOT NULL,
+    "priority_fee_lookback_epochs" SMALLINT NOT NULL,
+    "priority_fee_lookback_offset" SMALLINT NOT NULL,
+    "priority_fee_max_commission_bps" INTEGER NOT NULL,
+    "priority_fee_error_margin_bps" INTEGER NOT NULL,
+    "num_delegation_validators" BIGINT NOT NULL,
+    "scoring_unstake_cap_bps" BIGINT NOT NULL,
+    "instant_unstake_cap_bps" BIGINT NOT NULL,
+    "stake_deposit_unstake_cap_bps" BIGINT NOT NULL,
+    "instant_unstake_epoch_progress" DOUBLE PRECISION NOT NULL,
+    "compute_score_slot_range" "public"."u_64" NOT NULL,
+    "instant_unstake_inputs_epoch_progress" DOUBLE PRECISION NOT NULL,
+    "num_epochs_between_scoring" "public"."u_64" NOT NULL,
+    "minimum_stake_lamports" "public"."u_64" NOT NULL,
+    "minimum_voting_epochs" "public"."u_64" NOT NULL,
+    "priority_fee_scoring_start_epoch" INTEGER NOT NULL
+);
+
+--
+-- Row Level Security Policies
+--
+ALTER TABLE "public"."steward_config_history" ENABLE ROW LEVEL SECURITY;
+
+-- Policy: Enable read access for all users
+CREATE POLICY "Enable read access for all users" ON "public"."steward_config_history"
+    FOR SELECT
+        USING (TRUE);
\ No newline at end of file
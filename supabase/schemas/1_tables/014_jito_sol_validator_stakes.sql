@@ -0,0 +1,22 @@
+--
+-- JitoSOL Validator Stakes Table
+--
+-- Stores the per-validator stake of the JitoSOL stake pool ValidatorList at each epoch
+--
+CREATE TABLE IF NOT EXISTS "public"."jito_sol_validator_stakes"(
+    "id" VARCHAR(70) NOT NULL PRIMARY KEY, -- {epoch}-{vote_pubkey}
+    "epoch" "public"."u_64" NOT NULL,
+    "vote_pubkey" "public"."solana_pubkey" NOT NULL,
+    "active_stake_lamports" "public"."u_64" NOT NULL,
+    "transient_stake_lamports" "public"."u_64" NOT NULL
+);
+
+--
+-- Row Level Security Policies
+--
+ALTER TABLE "public"."jito_sol_validator_stakes" ENABLE ROW LEVEL SECURITY;
+
+-- Policy: Enable read access for all users
+CREATE POLICY "Enable read access for all users" ON "public"."jito_sol_validator_stakes"
+    FOR SELECT
+        USING (TRUE);
\ No newline at end of file
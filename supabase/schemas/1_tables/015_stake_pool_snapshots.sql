@@ -0,0 +1,33 @@
+--
+-- Stake Pool Snapshots Table
+--
+-- Stores the JitoSOL stake pool exchange rate, reserve balance and fees at each epoch
+--
+CREATE TABLE IF NOT EXISTS "public"."stake_pool_snapshots"(
+    "epoch" "public"."u_64" NOT NULL PRIMARY KEY,
+    "total_lamports" "public"."u_64" NOT NULL,
+    "pool_token_supply" "public"."u_64" NOT NULL,
+    "reserve_lamports" "public"."u_64" NOT NULL,
+    "epoch_fee_numerator" "public"."u_64" NOT NULL,
+    "epoch_fee_denominator" "public"."u_64" NOT NULL,
+    "stake_deposit_fee_numerator" "public"."u_64" NOT NULL,
+    "stake_deposit_fee_denominator" "public"."u_64" NOT NULL,
+    "stake_withdrawal_fee_numerator" "public"."u_64" NOT NULL,
+    "stake_withdrawal_fee_denominator" "public"."u_64" NOT NULL,
+    "sol_deposit_fee_numerator" "public"."u_64" NOT NULL,
+    "sol_deposit_fee_denominator" "public"."u_64" NOT NULL,
+    "sol_withdrawal_fee_numerator" "public"."u_64" NOT NULL,
+    "sol_withdrawal_fee_denominator" "public"."u_64" NOT NULL,
+    "stake_referral_fee" SMALLINT NOT NULL,
+    "sol_referral_fee" SMALLINT NOT NULL
+);
+
+--
+-- Row Level Security Policies
+--
+ALTER TABLE "public"."stake_pool_snapshots" ENABLE ROW LEVEL SECURITY;
+
+-- Policy: Enable read access for all users
+CREATE POLICY "Enable read access for all users" ON "public"."stake_pool_snapshots"
+    FOR SELECT
+        USING (TRUE);
\ No newline at end of file
@@ -0,0 +1,28 @@
+--
+-- Steward Validator States Table
+--
+-- Stores the per-validator scores, delegations and flags of the on-chain steward state at each epoch
+--
+CREATE TABLE IF NOT EXISTS "public"."steward_validator_states"(
+    "id" VARCHAR(70) NOT NULL PRIMARY KEY, -- {epoch}-{vote_pubkey}
+    "epoch" "public"."u_64" NOT NULL,
+    "cycle_start_epoch" "public"."u_64" NOT NULL,
+    "vote_pubkey" "public"."solana_pubkey" NOT NULL,
+    "validator_index" BIGINT NOT NULL,
+    "score" BIGINT NOT NULL,
+    "yield_score" BIGINT NOT NULL,
+    "delegation_numerator" BIGINT NOT NULL,
+    "delegation_denominator" BIGINT NOT NULL,
+    "instant_unstake" BOOLEAN NOT NULL,
+    "progress" BOOLEAN NOT NULL
+);
+
+--
+-- Row Level Security Policies
+--
+ALTER TABLE "public"."steward_validator_states" ENABLE ROW LEVEL SECURITY;
+
+-- Policy: Enable read access for all users
+CREATE POLICY "Enable read access for all users" ON "public"."steward_validator_states"
+    FOR SELECT
+        USING (TRUE);
\ No newline at end of file
@@ -0,0 +1,28 @@
+--
+-- Epoch Block Production Table
+--
+-- Stores how each leader's slots in an epoch turned out: produced blocks, skipped slots and slots the RPC returned
+-- no block for without confirming the skip
+--
+CREATE TABLE IF NOT EXISTS "public"."epoch_block_production"(
+    "id" VARCHAR(70) NOT NULL PRIMARY KEY, -- concatenation of {epoch}-{identity_pubkey}
+    "identity_pubkey" "public"."solana_pubkey" NOT NULL,
+    "epoch" "public"."u_64" NOT NULL,
+    "leader_slots" BIGINT NOT NULL,
+    "blocks_produced" BIGINT NOT NULL,
+    "skipped_slots" BIGINT NOT NULL,
+    "rpc_missing_slots" BIGINT NOT NULL
+);
+
+-- INDEXES
+CREATE INDEX "idx_epoch_block_production_by_identity_and_epoch" ON "public"."epoch_block_production" USING "btree"("identity_pubkey", "epoch");
+
+--
+-- Row Level Security Policies
+--
+ALTER TABLE "public"."epoch_block_production" ENABLE ROW LEVEL SECURITY;
+
+-- Policy: Enable read access for all users
+CREATE POLICY "Enable read access for all users" ON "public"."epoch_block_production"
+    FOR SELECT
+        USING (TRUE);
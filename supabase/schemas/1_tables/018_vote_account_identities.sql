@@ -0,0 +1,25 @@
+--
+-- Vote Account Identities Table
+--
+-- Stores the node identity each vote account voted for at each epoch, used to join the data recorded by leader
+-- identity with vote accounts
+--
+CREATE TABLE IF NOT EXISTS "public"."vote_account_identities"(
+    "id" VARCHAR(70) NOT NULL PRIMARY KEY, -- concatenation of {epoch}-{vote_pubkey}
+    "vote_pubkey" "public"."solana_pubkey" NOT NULL,
+    "identity_pubkey" "public"."solana_pubkey" NOT NULL,
+    "epoch" "public"."u_64" NOT NULL
+);
+
+-- INDEXES
+CREATE INDEX "idx_vote_account_identities_by_identity_and_epoch" ON "public"."vote_account_identities" USING "btree"("identity_pubkey", "epoch");
+
+--
+-- Row Level Security Policies
+--
+ALTER TABLE "public"."vote_account_identities" ENABLE ROW LEVEL SECURITY;
+
+-- Policy: Enable read access for all users
+CREATE POLICY "Enable read access for all users" ON "public"."vote_account_identities"
+    FOR SELECT
+        USING (TRUE);